@@ -0,0 +1,37 @@
+use aoc2017::day02::PairSearch;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// A row of `width` pseudo-random values below `max`, from a fixed seed so that runs compare.
+fn row(width: usize, max: u32) -> Vec<u32> {
+    let mut state: u64 = 0x2017;
+
+    (0..width)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) as u32 % max + 1
+        })
+        .collect()
+}
+
+fn divisible_pairs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day02_divisible_pairs");
+
+    for width in [100, 1_000, 10_000] {
+        let row = row(width, 1_000_000);
+
+        for search in [PairSearch::Pairwise, PairSearch::Multiples] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", search), width),
+                &row,
+                |b, row| b.iter(|| search.find(row)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, divisible_pairs);
+criterion_main!(benches);
@@ -0,0 +1,80 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// Generates a test per part for every `examples/dayNN_<name>.txt` that has a sibling
+// `dayNN_<name>.expected` listing `<part> <answer>` lines. See `tests/examples.rs`.
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let examples_dir = Path::new("examples");
+
+    println!("cargo:rerun-if-changed={}", examples_dir.display());
+
+    let mut examples: Vec<_> = fs::read_dir(examples_dir)
+        .map(|entries| entries.map(|entry| entry.unwrap().path()).collect())
+        .unwrap_or_default();
+    examples.sort();
+
+    let mut tests = String::new();
+
+    for expected_path in examples
+        .iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "expected"))
+    {
+        println!("cargo:rerun-if-changed={}", expected_path.display());
+
+        let stem = expected_path.file_stem().unwrap().to_str().unwrap();
+        let day: u8 = stem
+            .strip_prefix("day")
+            .and_then(|rest| rest.get(..2))
+            .and_then(|day| day.parse().ok())
+            .unwrap_or_else(|| panic!("{} is not named dayNN_<name>", expected_path.display()));
+
+        let input_path = expected_path.with_extension("txt");
+        assert!(
+            input_path.exists(),
+            "{} has no matching input",
+            expected_path.display()
+        );
+        println!("cargo:rerun-if-changed={}", input_path.display());
+
+        let ident: String = stem
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        for line in fs::read_to_string(expected_path).unwrap().lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (part, answer) = line.split_once(char::is_whitespace).unwrap_or_else(|| {
+                panic!("{}: expected `<part> <answer>`", expected_path.display())
+            });
+            let variant = match part {
+                "1" => "One",
+                "2" => "Two",
+                _ => panic!("{}: part must be 1 or 2", expected_path.display()),
+            };
+
+            tests.push_str(&format!(
+                "#[test]\nfn {ident}_part_{part}() {{\n    check({day}, include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/examples/{stem}.txt\")), Part::{variant}, {answer:?});\n}}\n\n",
+                ident = ident,
+                part = part,
+                day = day,
+                stem = stem,
+                variant = variant,
+                answer = answer.trim(),
+            ));
+        }
+    }
+
+    fs::write(Path::new(&out_dir).join("examples.rs"), tests).unwrap();
+}
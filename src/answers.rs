@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+
+use crate::registry::Part;
+
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Known answers to the real inputs, stored one per line as `<day> <part> <answer>`. A line without
+/// an answer is a placeholder for one that isn't known yet. Blank lines and lines starting with `#`
+/// are ignored.
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    inner: BTreeMap<(u8, Part), Option<String>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> anyhow::Result<Self> {
+        let mut inner = BTreeMap::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.splitn(3, char::is_whitespace);
+            let (day, part, answer) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(day), Some(part), answer) => (day, part, answer.map(str::trim)),
+                _ => bail!("Line {}: expected `<day> <part> <answer>`", i + 1),
+            };
+
+            let day = day
+                .parse()
+                .with_context(|| format!("Line {}: invalid day {}", i + 1, day))?;
+            let part = part.parse().map_err(|e| anyhow!("Line {}: {}", i + 1, e))?;
+
+            if inner
+                .insert((day, part), answer.map(str::to_owned))
+                .is_some()
+            {
+                bail!(
+                    "Line {}: duplicate answer for day {} part {}",
+                    i + 1,
+                    day,
+                    part
+                );
+            }
+        }
+
+        Ok(Self { inner })
+    }
+
+    /// Loads the answers file, treating a missing file as having no known answers.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let input = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read answers file {}", path.display()))?;
+
+        Self::parse(&input).with_context(|| format!("Invalid answers file {}", path.display()))
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.inner.get(&(day, part))?.as_deref()
+    }
+
+    pub fn check(&self, day: u8, part: Part, answer: &str) -> Verdict {
+        match self.get(day, part) {
+            None => Verdict::Missing,
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_owned(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => f.pad("pass"),
+            Verdict::Fail { .. } => f.pad("FAIL"),
+            Verdict::Missing => f.pad("missing"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_works() {
+        let input = "\
+            # day part answer
+            1 1 1089
+
+            10 2 23234babdc6afa036749cfa9b597de1b";
+
+        let answers = Answers::parse(input).unwrap();
+
+        assert_eq!(Some("1089"), answers.get(1, Part::One));
+        assert_eq!(None, answers.get(1, Part::Two));
+        assert_eq!(
+            Some("23234babdc6afa036749cfa9b597de1b"),
+            answers.get(10, Part::Two)
+        );
+    }
+
+    #[test]
+    fn parse_allows_placeholders() {
+        let answers = Answers::parse("14 1\n14 2 42").unwrap();
+
+        assert_eq!(None, answers.get(14, Part::One));
+        assert_eq!(Some("42"), answers.get(14, Part::Two));
+        assert!(Answers::parse("14 1\n14 1 42").is_err());
+    }
+
+    #[test]
+    fn parse_rejects_malformed_lines() {
+        assert!(Answers::parse("1").is_err());
+        assert!(Answers::parse("1 3 42").is_err());
+        assert!(Answers::parse("1 1 42\n1 1 43").is_err());
+    }
+
+    #[test]
+    fn check_works() {
+        let answers = Answers::parse("7 1 azqje").unwrap();
+
+        assert_eq!(Verdict::Pass, answers.check(7, Part::One, "azqje"));
+        assert_eq!(
+            Verdict::Fail {
+                expected: "azqje".to_owned()
+            },
+            answers.check(7, Part::One, "tknk")
+        );
+        assert_eq!(Verdict::Missing, answers.check(7, Part::Two, "646"));
+    }
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use serde::{Serialize, Serializer};
+
+use crate::registry::{Day, Part};
+
+/// Timings for a single step over every iteration of a benchmark.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    #[serde(rename = "min_ns", serialize_with = "as_nanos")]
+    pub min: Duration,
+    #[serde(rename = "median_ns", serialize_with = "as_nanos")]
+    pub median: Duration,
+    #[serde(rename = "mean_ns", serialize_with = "as_nanos")]
+    pub mean: Duration,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayBench {
+    pub day: u8,
+    pub iterations: usize,
+    pub parse: Stats,
+    pub part_1: Stats,
+    pub part_2: Stats,
+}
+
+impl Stats {
+    /// Panics if there are no samples.
+    pub fn from_samples(samples: &mut [Duration]) -> Self {
+        assert!(!samples.is_empty(), "Cannot summarise zero samples");
+        samples.sort_unstable();
+
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+
+        Self {
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / n as u32,
+        }
+    }
+}
+
+/// Runs the parse step and both parts of `day` `iterations` times, timing each step separately.
+pub fn bench(day: &Day, input: &str, iterations: usize) -> anyhow::Result<DayBench> {
+    anyhow::ensure!(iterations > 0, "Must run at least one iteration");
+
+    let mut parse = Vec::with_capacity(iterations);
+    let mut part_1 = Vec::with_capacity(iterations);
+    let mut part_2 = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let run = day.run(input, &Part::ALL)?;
+        parse.push(run.parse_time);
+
+        for part_run in run.parts {
+            match part_run.part {
+                Part::One => part_1.push(part_run.time),
+                Part::Two => part_2.push(part_run.time),
+            }
+        }
+    }
+
+    Ok(DayBench {
+        day: day.number,
+        iterations,
+        parse: Stats::from_samples(&mut parse),
+        part_1: Stats::from_samples(&mut part_1),
+        part_2: Stats::from_samples(&mut part_2),
+    })
+}
+
+fn as_nanos<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_nanos() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_works() {
+        let mut samples = [5, 1, 3, 7].map(Duration::from_millis);
+        let stats = Stats::from_samples(&mut samples);
+
+        assert_eq!(Duration::from_millis(1), stats.min);
+        assert_eq!(Duration::from_millis(4), stats.median);
+        assert_eq!(Duration::from_millis(4), stats.mean);
+
+        let mut samples = [9, 1, 2].map(Duration::from_millis);
+        let stats = Stats::from_samples(&mut samples);
+
+        assert_eq!(Duration::from_millis(2), stats.median);
+        assert_eq!(Duration::from_millis(4), stats.mean);
+    }
+
+    #[test]
+    fn stats_serialize_as_nanos() {
+        let mut samples = [Duration::from_micros(2)];
+        let stats = Stats::from_samples(&mut samples);
+
+        assert_eq!(
+            r#"{"min_ns":2000,"median_ns":2000,"mean_ns":2000}"#,
+            serde_json::to_string(&stats).unwrap()
+        );
+    }
+}
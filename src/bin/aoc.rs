@@ -0,0 +1,459 @@
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use aoc2017::answers::{Answers, Verdict, ANSWERS_FILE};
+use aoc2017::bench::{self, Stats};
+use aoc2017::budget::{self, Limits};
+use aoc2017::day04::{self, Normalization, Policy, Rule};
+use aoc2017::fetch::{self, Fetched, DEFAULT_BASE_URL};
+use aoc2017::input::{InputSource, DEFAULT_INPUTS_DIR};
+use aoc2017::output::{Format, Record, RecordWriter};
+use aoc2017::profile;
+use aoc2017::registry::{self, Day, Part, PartRun};
+use aoc2017::runner::{self, Outcome};
+use aoc2017::scaffold;
+use clap::{Args, Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(about = "Advent of Code 2017 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs the selected days, e.g. `7`, `all`, `3..9` or `3..=9`
+    Run {
+        days: String,
+        /// Only run the given part
+        #[arg(long)]
+        part: Option<Part>,
+        /// One of `human`, `json` (JSON lines) or `csv`
+        #[arg(long, default_value = "human")]
+        format: Format,
+        /// Number of days to run at once, defaults to one per core
+        #[arg(long)]
+        jobs: Option<usize>,
+        #[command(flatten)]
+        limits: LimitArgs,
+        #[command(flatten)]
+        inputs: InputArgs,
+    },
+    /// Times the parse step and both parts of the selected days over many iterations
+    Bench {
+        days: String,
+        #[arg(long, default_value_t = 20)]
+        iterations: usize,
+        /// Print the results as JSON
+        #[arg(long)]
+        json: bool,
+        #[command(flatten)]
+        inputs: InputArgs,
+    },
+    /// Downloads the input for a day into the inputs directory, unless it is already there
+    Fetch {
+        day: u8,
+        /// Server to fetch from, for testing against a stub
+        #[arg(long, env = "AOC_BASE_URL", default_value = DEFAULT_BASE_URL)]
+        base_url: String,
+        /// File containing the session token, used when $SESSION_TOKEN is not set
+        #[arg(long, env = "AOC_SESSION_FILE")]
+        session_file: Option<PathBuf>,
+        #[command(flatten)]
+        inputs_dir: InputsDir,
+    },
+    /// Generates the module, registry entry, answers placeholder and input file for a new day
+    New { day: u8 },
+    /// Checks the day 4 passphrases against a configurable set of rules, explaining each rejection
+    Passphrases {
+        /// `exact` to reject repeated words, or `anagram` to reject anagrams as well
+        #[arg(long, default_value = "exact")]
+        rule: Rule,
+        /// Compare words ignoring case
+        #[arg(long)]
+        ignore_case: bool,
+        /// Normalise words with `nfc` or `nfkc` before comparing them
+        #[arg(long)]
+        normalize: Option<Normalization>,
+        /// Reject passphrases with fewer words than this
+        #[arg(long, default_value_t = 0)]
+        min_words: usize,
+        /// Reject passphrases containing any of these comma-separated words
+        #[arg(long, value_delimiter = ',')]
+        forbid: Vec<String>,
+        /// Print the valid passphrases too
+        #[arg(long)]
+        all: bool,
+        #[command(flatten)]
+        inputs: InputArgs,
+    },
+    /// Checks the answers for the selected days against the known answers in the inputs directory
+    Verify {
+        #[arg(default_value = "all")]
+        days: String,
+        /// Check the answers of every profile, and of the inputs directory itself if it has an
+        /// answers file, skipping days a profile has no input for
+        #[arg(long, conflicts_with = "profile")]
+        all_profiles: bool,
+        #[command(flatten)]
+        limits: LimitArgs,
+        #[command(flatten)]
+        inputs_dir: InputsDir,
+    },
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Read the input from this file instead, or from stdin if `-`. Only valid for a single day
+    #[arg(long)]
+    input: Option<String>,
+    #[command(flatten)]
+    inputs_dir: InputsDir,
+}
+
+#[derive(Args)]
+struct InputsDir {
+    /// Directory containing the `dayNN.txt` inputs and their `answers.txt`
+    #[arg(long, env = "AOC_INPUTS_DIR", default_value = DEFAULT_INPUTS_DIR)]
+    inputs_dir: PathBuf,
+    /// Use the inputs and answers of this profile, kept in a subdirectory of the inputs directory
+    #[arg(long, env = "AOC_PROFILE")]
+    profile: Option<String>,
+}
+
+impl InputsDir {
+    fn dir(&self) -> anyhow::Result<PathBuf> {
+        match &self.profile {
+            Some(profile) => profile::profile_dir(&self.inputs_dir, profile),
+            None => Ok(self.inputs_dir.clone()),
+        }
+    }
+}
+
+#[derive(Args)]
+struct LimitArgs {
+    /// Give up on a part after this many iterations of its main loop
+    #[arg(long)]
+    max_iterations: Option<u64>,
+    /// Give up on a part after this many seconds
+    #[arg(long, value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+}
+
+impl LimitArgs {
+    fn apply(&self) {
+        budget::set_default_limits(Limits {
+            max_iterations: self.max_iterations,
+            timeout: self.timeout,
+        });
+    }
+}
+
+fn parse_seconds(seconds: &str) -> Result<Duration, String> {
+    seconds
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("expected a number of seconds, not {}", seconds))
+}
+
+impl InputArgs {
+    fn select(&self, days: &str) -> anyhow::Result<Vec<&'static Day>> {
+        let days = registry::select(days)?;
+        if self.input.is_some() && days.len() > 1 {
+            anyhow::bail!("--input can only be used with a single day");
+        }
+
+        Ok(days)
+    }
+
+    fn source(&self, day: u8) -> anyhow::Result<InputSource> {
+        match &self.input {
+            Some(input) => Ok(InputSource::from_arg(input)),
+            None => Ok(InputSource::for_day(&self.inputs_dir.dir()?, day)),
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Run {
+            days,
+            part,
+            format,
+            jobs,
+            limits,
+            inputs,
+        } => {
+            limits.apply();
+            run(&days, part, format, jobs, &inputs)
+        }
+        Command::Bench {
+            days,
+            iterations,
+            json,
+            inputs,
+        } => bench(&days, iterations, json, &inputs),
+        Command::Fetch {
+            day,
+            base_url,
+            session_file,
+            inputs_dir,
+        } => fetch(day, &base_url, session_file, &inputs_dir),
+        Command::New { day } => new(day),
+        Command::Passphrases {
+            rule,
+            ignore_case,
+            normalize,
+            min_words,
+            forbid,
+            all,
+            inputs,
+        } => {
+            let policy = Policy::new(rule)
+                .case_insensitive(ignore_case)
+                .normalized(normalize)
+                .min_words(min_words)
+                .forbid(forbid);
+            passphrases(&policy, all, &inputs)
+        }
+        Command::Verify {
+            days,
+            all_profiles,
+            limits,
+            inputs_dir,
+        } => {
+            limits.apply();
+            verify(&days, all_profiles, &inputs_dir)
+        }
+    }
+}
+
+fn run(
+    days: &str,
+    part: Option<Part>,
+    format: Format,
+    jobs: Option<usize>,
+    inputs: &InputArgs,
+) -> anyhow::Result<()> {
+    let parts = match part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+    let days = inputs.select(days)?;
+
+    // Panics are reported in the summary below, so don't also dump them as they happen
+    panic::set_hook(Box::new(|_| {}));
+    let outcomes =
+        runner::run_parallel(&days, &parts, jobs, |day| inputs.source(day.number)?.read())?;
+    let _ = panic::take_hook();
+
+    let mut writer = RecordWriter::new(format, io::stdout().lock());
+    let mut failures = Vec::new();
+
+    for (day, outcome) in days.iter().zip(outcomes) {
+        match outcome {
+            Outcome::Solved(run) => {
+                for record in Record::from_run(day.number, &run) {
+                    writer.write(&record)?;
+                }
+            }
+            Outcome::Failed(e) => failures.push((day.number, format!("{:#}", e))),
+            Outcome::Panicked(message) => {
+                failures.push((day.number, format!("panicked: {}", message)))
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        eprintln!();
+        for (day, message) in &failures {
+            eprintln!("Day {:>2} failed: {}", day, message);
+        }
+        anyhow::bail!("{} of {} day(s) failed", failures.len(), days.len());
+    }
+
+    Ok(())
+}
+
+fn verify(days: &str, all_profiles: bool, inputs_dir: &InputsDir) -> anyhow::Result<()> {
+    let days = registry::select(days)?;
+
+    let failures = if all_profiles {
+        let root = &inputs_dir.inputs_dir;
+        let mut dirs = Vec::new();
+        if root.join(ANSWERS_FILE).exists() {
+            dirs.push(("(default)".to_owned(), root.clone()));
+        }
+        for profile in profile::list(root)? {
+            let dir = profile::profile_dir(root, &profile)?;
+            dirs.push((profile, dir));
+        }
+
+        let mut failures = 0;
+        for (i, (name, dir)) in dirs.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("Profile {}", name);
+            failures += verify_dir(&days, dir, true)?;
+        }
+        failures
+    } else {
+        verify_dir(&days, &inputs_dir.dir()?, false)?
+    };
+
+    if failures > 0 {
+        anyhow::bail!("{} answer(s) did not verify", failures);
+    }
+
+    Ok(())
+}
+
+/// Prints a row per answer for `days` against the answers in `inputs_dir`, returning how many
+/// failed.
+fn verify_dir(days: &[&Day], inputs_dir: &Path, skip_missing: bool) -> anyhow::Result<usize> {
+    let answers = Answers::load(&inputs_dir.join(ANSWERS_FILE))?;
+    let mut failures = 0;
+
+    println!(
+        "{:>3}  {:>4}  {:<7}  {:<32}  Expected",
+        "Day", "Part", "Status", "Answer"
+    );
+
+    for day in days {
+        if skip_missing && fetch::cached(inputs_dir, day.number).is_none() {
+            continue;
+        }
+
+        let results = InputSource::for_day(inputs_dir, day.number)
+            .read()
+            .and_then(|input| day.run(&input, &Part::ALL));
+
+        let run = match results {
+            Ok(run) => run,
+            Err(e) => {
+                failures += 1;
+                println!("{:>3}  {:>4}  {:<7}  {:#}", day.number, "-", "ERROR", e);
+                continue;
+            }
+        };
+
+        for PartRun { part, answer, .. } in run.parts {
+            let verdict = answers.check(day.number, part, &answer);
+            let expected = match &verdict {
+                Verdict::Fail { expected } => {
+                    failures += 1;
+                    expected.as_str()
+                }
+                _ => "",
+            };
+
+            let row = format!(
+                "{:>3}  {:>4}  {:<7}  {:<32}  {}",
+                day.number, part, verdict, answer, expected
+            );
+            println!("{}", row.trim_end());
+        }
+    }
+
+    Ok(failures)
+}
+
+fn bench(days: &str, iterations: usize, json: bool, inputs: &InputArgs) -> anyhow::Result<()> {
+    let mut results = Vec::new();
+
+    if !json {
+        println!(
+            "{:>3}  {:<6}  {:>12}  {:>12}  {:>12}",
+            "Day", "Step", "Min", "Median", "Mean"
+        );
+    }
+
+    for day in inputs.select(days)? {
+        let input = inputs.source(day.number)?.read()?;
+        let result = bench::bench(day, &input, iterations)?;
+
+        if !json {
+            let steps = [
+                ("parse", &result.parse),
+                ("part 1", &result.part_1),
+                ("part 2", &result.part_2),
+            ];
+
+            for (step, Stats { min, median, mean }) in steps {
+                println!(
+                    "{:>3}  {:<6}  {:>12.2?}  {:>12.2?}  {:>12.2?}",
+                    day.number, step, min, median, mean
+                );
+            }
+        }
+
+        results.push(result);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    }
+
+    Ok(())
+}
+
+fn passphrases(policy: &Policy, all: bool, inputs: &InputArgs) -> anyhow::Result<()> {
+    let input = inputs.source(4)?.read()?;
+    let passphrases = day04::validate(&input, policy);
+    let valid = passphrases.iter().filter(|p| p.is_valid()).count();
+
+    for passphrase in &passphrases {
+        if all || !passphrase.is_valid() {
+            println!("{}", passphrase);
+        }
+    }
+    println!("{} of {} passphrases are valid", valid, passphrases.len());
+
+    Ok(())
+}
+
+fn new(day: u8) -> anyhow::Result<()> {
+    for path in scaffold::scaffold(Path::new("."), day)? {
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn fetch(
+    day: u8,
+    base_url: &str,
+    session_file: Option<PathBuf>,
+    inputs_dir: &InputsDir,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        (1..=25).contains(&day),
+        "Day must be between 1 and 25, not {}",
+        day
+    );
+
+    let profile = inputs_dir.profile.as_deref();
+    let inputs_dir = inputs_dir.dir()?;
+
+    if let Some(path) = fetch::cached(&inputs_dir, day) {
+        println!("{} already exists", path.display());
+        return Ok(());
+    }
+
+    let session_file = session_file.unwrap_or_else(|| fetch::default_session_file(profile));
+    let token = fetch::session_token(&session_file)?;
+
+    match fetch::fetch(base_url, &token, day, &inputs_dir)? {
+        Fetched::Cached(path) => println!("{} already exists", path.display()),
+        Fetched::Downloaded(path) => println!("Wrote {}", path.display()),
+    }
+
+    Ok(())
+}
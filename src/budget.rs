@@ -0,0 +1,139 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::{BudgetExhausted, Limit, Result};
+
+/// How often `Budget::tick` looks at the clock, as reading it on every iteration would dominate
+/// the tighter loops.
+const CLOCK_INTERVAL: u64 = 1024;
+
+static DEFAULT_LIMITS: Mutex<Limits> = Mutex::new(Limits::UNLIMITED);
+
+/// Bounds on how much work an open-ended solver may do before giving up.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub max_iterations: Option<u64>,
+    pub timeout: Option<Duration>,
+}
+
+impl Limits {
+    pub const UNLIMITED: Limits = Limits {
+        max_iterations: None,
+        timeout: None,
+    };
+}
+
+/// Sets the limits used by `Budget::from_defaults`, and so by every `Solution` impl.
+pub fn set_default_limits(limits: Limits) {
+    *DEFAULT_LIMITS.lock().unwrap() = limits;
+}
+
+pub fn default_limits() -> Limits {
+    *DEFAULT_LIMITS.lock().unwrap()
+}
+
+/// Counts the iterations of a solver's main loop against its `Limits`. The clock starts when the
+/// budget is created.
+#[derive(Debug, Clone)]
+pub struct Budget {
+    max_iterations: Option<u64>,
+    deadline: Option<(Instant, Duration)>,
+    iterations: u64,
+}
+
+impl Budget {
+    pub fn new(limits: Limits) -> Self {
+        Self {
+            max_iterations: limits.max_iterations,
+            deadline: limits
+                .timeout
+                .map(|timeout| (Instant::now() + timeout, timeout)),
+            iterations: 0,
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(Limits::UNLIMITED)
+    }
+
+    pub fn from_defaults() -> Self {
+        Self::new(default_limits())
+    }
+
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    /// Records one iteration, failing once either limit has been exceeded.
+    pub fn tick(&mut self) -> Result<()> {
+        self.iterations += 1;
+
+        if let Some(max) = self.max_iterations {
+            if self.iterations > max {
+                return Err(self.exhausted(Limit::Iterations(max)));
+            }
+        }
+
+        if let Some((deadline, timeout)) = self.deadline {
+            if self.iterations.is_multiple_of(CLOCK_INTERVAL) && Instant::now() >= deadline {
+                return Err(self.exhausted(Limit::Timeout(timeout)));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn exhausted(&self, limit: Limit) -> crate::error::Error {
+        BudgetExhausted {
+            iterations: self.iterations - 1,
+            limit,
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn max_iterations_is_enforced() {
+        let mut budget = Budget::new(Limits {
+            max_iterations: Some(3),
+            timeout: None,
+        });
+
+        for _ in 0..3 {
+            budget.tick().unwrap();
+        }
+
+        assert_eq!(
+            Error::BudgetExhausted(BudgetExhausted {
+                iterations: 3,
+                limit: Limit::Iterations(3)
+            }),
+            budget.tick().unwrap_err()
+        );
+    }
+
+    #[test]
+    fn timeout_is_enforced() {
+        let mut budget = Budget::new(Limits {
+            max_iterations: None,
+            timeout: Some(Duration::ZERO),
+        });
+
+        let err = std::iter::repeat_with(|| budget.tick())
+            .find_map(|tick| tick.err())
+            .unwrap();
+
+        assert!(matches!(
+            err,
+            Error::BudgetExhausted(BudgetExhausted {
+                iterations,
+                limit: Limit::Timeout(Duration::ZERO),
+            }) if iterations == CLOCK_INTERVAL - 1
+        ));
+    }
+}
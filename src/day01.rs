@@ -1,50 +1,329 @@
-use itertools::Itertools;
+use std::collections::VecDeque;
+use std::io::{BufReader, Read};
 
-pub fn part_1(input: &str) -> u32 {
-    input
-        .chars()
-        .chain(input.chars().take(1))
-        .filter_map(|c| c.to_digit(10))
-        .tuple_windows()
-        .fold(0, |acc, (a, b)| if a == b { acc + a } else { acc })
+use crate::error::{ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<u8>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_digits(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(part_1(input))
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(part_2(input))
+    }
+}
+
+/// Below this many matches the sum of a chunk fits in a `u32`, which vectorises better than `u64`.
+const CHUNK_SIZE: usize = 1 << 16;
+
+pub fn part_1(digits: &[u8]) -> u64 {
+    captcha_digits(digits, 0, 1)
+}
+
+pub fn part_2(digits: &[u8]) -> u64 {
+    captcha_digits(digits, 0, digits.len() as isize / 2)
+}
+
+/// Sums the digits of `input` that match the digit `offset` places further round the circle. The
+/// offset may be negative or longer than the input.
+pub fn captcha(input: &str, offset: isize) -> Result<u64> {
+    let bytes = input
+        .trim_end_matches(|c: char| c.is_ascii_whitespace())
+        .as_bytes();
+
+    if !bytes.iter().all(u8::is_ascii_digit) {
+        return Err(parse_digits(input).expect_err("input contains a non-digit"));
+    }
+
+    Ok(captcha_digits(bytes, b'0', offset))
+}
+
+/// The straightforward version of `captcha`, kept as a reference for the faster one.
+pub fn captcha_iter(input: &str, offset: isize) -> Result<u64> {
+    let digits = parse_digits(input)?;
+    if digits.is_empty() {
+        return Ok(0);
+    }
+
+    let skip = offset.rem_euclid(digits.len() as isize) as usize;
+
+    Ok(digits
+        .iter()
+        .zip(digits.iter().cycle().skip(skip))
+        .filter(|(a, b)| a == b)
+        .map(|(&a, _)| u64::from(a))
+        .sum())
+}
+
+/// Like `captcha`, but reads the digits from `reader` as they are needed, keeping only the first
+/// and last `offset` of them in memory.
+pub fn captcha_reader<R: Read>(reader: R, offset: isize) -> anyhow::Result<u64> {
+    // Matching is symmetric, so an offset of -k pairs up the same digits as k, and once the input
+    // is longer than k, k is the offset modulo the length in one direction or the other
+    let k = offset.unsigned_abs();
+
+    let mut scanner = Scanner::default();
+    let mut head = Vec::new();
+    let mut window = VecDeque::new();
+    let mut count = 0;
+    let mut sum = 0;
+
+    for byte in BufReader::new(reader).bytes() {
+        let digit = match scanner.push(byte?)? {
+            Some(digit) => digit,
+            None => continue,
+        };
+        count += 1;
+
+        if k == 0 {
+            sum += u64::from(digit);
+            continue;
+        }
+
+        if window.len() == k && window.pop_front() == Some(digit) {
+            sum += u64::from(digit);
+        }
+        if head.len() < k {
+            head.push(digit);
+        }
+        window.push_back(digit);
+    }
+
+    if count <= k {
+        // The offset wraps round the whole input, which is all in `head`
+        return Ok(captcha_digits(&head, 0, offset));
+    }
+
+    // The pairs that wrap round from the last k digits to the first k
+    sum += window
+        .iter()
+        .zip(&head)
+        .filter(|(a, b)| a == b)
+        .map(|(&a, _)| u64::from(a))
+        .sum::<u64>();
+
+    Ok(sum)
+}
+
+/// The digits of `input`, which may only be followed by whitespace.
+pub fn parse_digits(input: &str) -> Result<Vec<u8>> {
+    let mut scanner = Scanner::default();
+    let mut digits = Vec::with_capacity(input.len());
+
+    for &byte in input.as_bytes() {
+        digits.extend(scanner.push(byte)?);
+    }
+
+    Ok(digits)
+}
+
+/// The captcha of `digits`, which are either digit values or ASCII digits depending on `zero`.
+fn captcha_digits(digits: &[u8], zero: u8, offset: isize) -> u64 {
+    let n = digits.len();
+    if n == 0 {
+        return 0;
+    }
+
+    // Rather than cycling, compare the input against itself shifted, then the part that wraps
+    let skip = offset.rem_euclid(n as isize) as usize;
+
+    sum_matches(&digits[..n - skip], &digits[skip..], zero)
+        + sum_matches(&digits[n - skip..], &digits[..skip], zero)
+}
+
+fn sum_matches(a: &[u8], b: &[u8], zero: u8) -> u64 {
+    a.chunks(CHUNK_SIZE)
+        .zip(b.chunks(CHUNK_SIZE))
+        .map(|(a, b)| {
+            let sum: u32 = a
+                .iter()
+                .zip(b)
+                .map(|(&a, &b)| if a == b { u32::from(a - zero) } else { 0 })
+                .sum();
+            u64::from(sum)
+        })
+        .sum()
 }
 
-pub fn part_2(input: &str) -> u32 {
-    input
-        .chars()
-        .zip(input.chars().cycle().skip(input.len() / 2))
-        .filter_map(|(a, b)| Some((a.to_digit(10)?, b.to_digit(10)?)))
-        .fold(0, |acc, (a, b)| if a == b { acc + a } else { acc })
+/// Turns bytes into digits one at a time, tracking positions so that anything other than trailing
+/// whitespace can be reported.
+#[derive(Debug, Default)]
+struct Scanner {
+    line: usize,
+    column: usize,
+    /// The first whitespace byte seen and its position, which is only allowed at the end
+    whitespace: Option<(usize, usize, u8)>,
+}
+
+impl Scanner {
+    fn push(&mut self, byte: u8) -> Result<Option<u8>> {
+        let (line, column) = (self.line + 1, self.column + 1);
+        if byte == b'\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += 1;
+        }
+
+        if byte.is_ascii_whitespace() {
+            self.whitespace.get_or_insert((line, column, byte));
+            return Ok(None);
+        }
+
+        if let Some((line, column, whitespace)) = self.whitespace {
+            return Err(not_a_digit(line, column, whitespace).into());
+        }
+
+        if byte.is_ascii_digit() {
+            Ok(Some(byte - b'0'))
+        } else {
+            Err(not_a_digit(line, column, byte).into())
+        }
+    }
+}
+
+fn not_a_digit(line: usize, column: usize, byte: u8) -> ParseError {
+    let message = if byte.is_ascii() {
+        format!("{:?} is not a digit", byte as char)
+    } else {
+        format!("byte {:#04x} is not a digit", byte)
+    };
+
+    ParseError::new(line, column, message)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
+    use proptest::prelude::*;
 
     #[test]
     fn part_1_works() {
-        let sum = part_1("1122");
+        let sum = part_1(&parse_digits("1122").unwrap());
         assert_eq!(3, sum);
 
-        let sum = part_1("91212129");
+        let sum = part_1(&parse_digits("91212129").unwrap());
         assert_eq!(9, sum);
     }
 
     #[test]
     fn part_2_works() {
-        let sum = part_2("12131415");
+        let sum = part_2(&parse_digits("12131415").unwrap());
         assert_eq!(4, sum);
 
-        let sum = part_2("1212");
+        let sum = part_2(&parse_digits("1212").unwrap());
         assert_eq!(6, sum);
 
-        let sum = part_2("123425");
+        let sum = part_2(&parse_digits("123425").unwrap());
         assert_eq!(4, sum);
 
-        let sum = part_2("123123");
+        let sum = part_2(&parse_digits("123123").unwrap());
         assert_eq!(12, sum);
 
-        let sum = part_2("1221");
+        let sum = part_2(&parse_digits("1221").unwrap());
         assert_eq!(0, sum);
     }
+
+    #[test]
+    fn captcha_handles_any_offset() {
+        assert_eq!(Ok(3), captcha("1122", 1));
+        assert_eq!(Ok(3), captcha("1122", -1));
+        assert_eq!(Ok(3), captcha("1122", 5));
+        assert_eq!(Ok(3), captcha("1122", -7));
+        assert_eq!(Ok(6), captcha("1122", 0));
+        assert_eq!(Ok(6), captcha("1122", 8));
+        assert_eq!(Ok(0), captcha("1122", 2));
+        assert_eq!(Ok(0), captcha("", 3));
+    }
+
+    #[test]
+    fn captcha_reader_matches_captcha() {
+        let inputs = ["", "7", "1122", "91212129", "12131415", "123425", "1221\n"];
+
+        for input in inputs {
+            for offset in -10..=10 {
+                assert_eq!(
+                    captcha(input, offset).unwrap(),
+                    captcha_reader(input.as_bytes(), offset).unwrap(),
+                    "{:?} with offset {}",
+                    input,
+                    offset
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn captcha_matches_captcha_iter_on_large_inputs() {
+        // Long enough to span several chunks, with runs so that plenty of digits match
+        let input: String = (0..3 * CHUNK_SIZE + 17)
+            .map(|i| char::from(b'0' + (i / 3 % 10) as u8))
+            .collect();
+
+        for offset in [
+            0,
+            1,
+            -1,
+            2,
+            3,
+            30,
+            1000,
+            CHUNK_SIZE as isize,
+            input.len() as isize / 2,
+        ] {
+            assert_eq!(captcha_iter(&input, offset), captcha(&input, offset));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn captcha_matches_captcha_iter(input in "[0-9]{0,200}\\n?", offset in -500isize..500) {
+            prop_assert_eq!(captcha_iter(&input, offset), captcha(&input, offset));
+        }
+
+        #[test]
+        fn captcha_reports_the_same_errors(input in "[0-9 x]{0,20}", offset in -50isize..50) {
+            prop_assert_eq!(captcha_iter(&input, offset), captcha(&input, offset));
+        }
+    }
+
+    #[test]
+    fn non_digits_are_reported() {
+        assert_eq!(
+            Err(Error::Parse(ParseError::new(1, 3, "'x' is not a digit"))),
+            captcha("12x4", 1)
+        );
+        assert_eq!(
+            Err(Error::Parse(ParseError::new(1, 3, "'\\n' is not a digit"))),
+            captcha("12\n34\n", 1)
+        );
+        assert_eq!(
+            Err(Error::Parse(ParseError::new(
+                1,
+                2,
+                "byte 0xc3 is not a digit"
+            ))),
+            captcha("1é", 1)
+        );
+
+        let err = captcha_reader("12 34".as_bytes(), 1).unwrap_err();
+        assert_eq!(
+            "Parse error at line 1, column 3: ' ' is not a digit",
+            err.to_string()
+        );
+    }
 }
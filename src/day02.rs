@@ -1,45 +1,414 @@
+use std::collections::HashMap;
+
+use std::fmt;
+
 use itertools::Itertools;
 
-pub fn part_1(input: &str) -> u32 {
-    use itertools::MinMaxResult::*;
-    let mut checksum = 0;
-
-    for line in input.lines() {
-        match line
-            .split_whitespace()
-            .filter_map(|c| c.parse::<u32>().ok())
-            .minmax()
-        {
-            NoElements => {}
-            OneElement(_) => {}
-            MinMax(min, max) => checksum += max - min,
+use crate::error::{Error, ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Input = Spreadsheet;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(Spreadsheet::parse(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(part_1(input))
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(part_2(input)?)
+    }
+}
+
+pub fn part_1(spreadsheet: &Spreadsheet) -> u64 {
+    spreadsheet.checksum(&MinMaxSpread)
+}
+
+/// Fails unless every row has exactly one evenly divisible pair.
+pub fn part_2(spreadsheet: &Spreadsheet) -> Result<u64> {
+    spreadsheet.divisible_pairs().strict_checksum()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spreadsheet {
+    /// Each row with the line it was on
+    rows: Vec<(usize, Vec<u32>)>,
+}
+
+/// Reduces a row of a spreadsheet to the value it adds to the checksum. Closures taking a row work
+/// as reducers too.
+pub trait RowReducer {
+    fn reduce(&self, row: &[u32]) -> u64;
+}
+
+/// The difference between the largest and smallest values, as in part 1.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MinMaxSpread;
+
+/// The result of dividing the only two values where one divides the other, as in part 2. Rows
+/// without such a pair count as 0, and rows with several use the first. See
+/// `Spreadsheet::divisible_pairs` to find out which rows those are.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DivisiblePair;
+
+/// A value in a row, with its 0-based column.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub column: usize,
+    pub value: u32,
+}
+
+/// Two values in a row where `divisor` divides `dividend` evenly.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PairMatch {
+    pub dividend: Cell,
+    pub divisor: Cell,
+}
+
+/// How to search a row for evenly divisible pairs. Every search finds the same pairs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PairSearch {
+    /// Tries every pair of values, which is quadratic in the length of the row
+    Pairwise,
+    /// Looks up the multiples of each distinct value up to the largest, which is quick for wide
+    /// rows as long as the values are not much larger than the number of them
+    Multiples,
+    /// Whichever of the others should take fewer steps for the row
+    Auto,
+}
+
+/// The evenly divisible pairs in a row. Only rows with exactly one are valid puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowPairs {
+    Unique(PairMatch),
+    Missing,
+    /// Every candidate, in column order
+    Ambiguous(Vec<PairMatch>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivisiblePairReport {
+    /// Each row's line and pairs
+    pub rows: Vec<(usize, RowPairs)>,
+}
+
+/// The sum of the greatest common divisors of every pair of values.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct PairwiseGcdSum;
+
+/// How far the value furthest from the median is from it, using the lower median of rows with
+/// an even number of values.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MedianSpread;
+
+impl Spreadsheet {
+    /// Parses rows of values separated by whitespace, or by commas if a row contains any. Blank
+    /// lines are skipped rather than treated as empty rows.
+    pub fn parse(input: &str) -> Result<Self> {
+        let rows = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| Ok((i + 1, parse_row(i + 1, line)?)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self { rows })
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[u32]> {
+        self.rows.iter().map(|(_, row)| row.as_slice())
+    }
+
+    /// Finds every row's evenly divisible pairs.
+    pub fn divisible_pairs(&self) -> DivisiblePairReport {
+        self.divisible_pairs_with(PairSearch::Auto)
+    }
+
+    pub fn divisible_pairs_with(&self, search: PairSearch) -> DivisiblePairReport {
+        let rows = self
+            .rows
+            .iter()
+            .map(|(line, row)| {
+                let mut pairs = search.find(row);
+                let pairs = match pairs.len() {
+                    0 => RowPairs::Missing,
+                    1 => RowPairs::Unique(pairs.remove(0)),
+                    _ => RowPairs::Ambiguous(pairs),
+                };
+
+                (*line, pairs)
+            })
+            .collect();
+
+        DivisiblePairReport { rows }
+    }
+
+    pub fn checksum<R: RowReducer + ?Sized>(&self, reducer: &R) -> u64 {
+        self.rows().map(|row| reducer.reduce(row)).sum()
+    }
+
+    /// Computes the checksum for each of `reducers` in a single pass over the rows.
+    pub fn checksums(&self, reducers: &[&dyn RowReducer]) -> Vec<u64> {
+        let mut checksums = vec![0; reducers.len()];
+
+        for row in self.rows() {
+            for (checksum, reducer) in checksums.iter_mut().zip(reducers) {
+                *checksum += reducer.reduce(row);
+            }
         }
+
+        checksums
     }
+}
 
-    checksum
+impl<F: Fn(&[u32]) -> u64> RowReducer for F {
+    fn reduce(&self, row: &[u32]) -> u64 {
+        self(row)
+    }
 }
 
-pub fn part_2(input: &str) -> u32 {
-    let mut checksum = 0;
+impl RowReducer for MinMaxSpread {
+    fn reduce(&self, row: &[u32]) -> u64 {
+        match row.iter().minmax().into_option() {
+            Some((min, max)) => u64::from(max - min),
+            None => 0,
+        }
+    }
+}
+
+impl RowReducer for DivisiblePair {
+    fn reduce(&self, row: &[u32]) -> u64 {
+        divisible_pairs(row).first().map_or(0, PairMatch::quotient)
+    }
+}
+
+impl PairMatch {
+    pub fn quotient(&self) -> u64 {
+        u64::from(self.dividend.value / self.divisor.value)
+    }
+}
+
+impl fmt::Display for RowPairs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs = match self {
+            RowPairs::Unique(pair) => std::slice::from_ref(pair),
+            RowPairs::Missing => return write!(f, "no evenly divisible pair"),
+            RowPairs::Ambiguous(pairs) => pairs.as_slice(),
+        };
+        let columns = pairs
+            .iter()
+            .map(|pair| format!("{}/{}", pair.dividend.column, pair.divisor.column))
+            .join(", ");
 
-    for line in input.lines() {
-        checksum += line
-            .split_whitespace()
-            .filter_map(|c| c.parse::<u32>().ok())
-            .sorted()
-            .rev()
+        match pairs.len() {
+            1 => write!(f, "1 evenly divisible pair, in columns {}", columns),
+            n => write!(f, "{} evenly divisible pairs, in columns {}", n, columns),
+        }
+    }
+}
+
+impl DivisiblePairReport {
+    /// Sums the quotients, skipping rows without a pair and using the first of several.
+    pub fn checksum(&self) -> u64 {
+        self.rows
+            .iter()
+            .map(|(_, pairs)| match pairs {
+                RowPairs::Unique(pair) => pair.quotient(),
+                RowPairs::Missing => 0,
+                RowPairs::Ambiguous(pairs) => pairs[0].quotient(),
+            })
+            .sum()
+    }
+
+    /// Sums the quotients, failing on the first row without exactly one pair.
+    pub fn strict_checksum(&self) -> Result<u64> {
+        let mut checksum = 0;
+
+        for (line, pairs) in &self.rows {
+            match pairs {
+                RowPairs::Unique(pair) => checksum += pair.quotient(),
+                _ => {
+                    return Err(Error::DivisiblePairs {
+                        line: *line,
+                        pairs: pairs.clone(),
+                    })
+                }
+            }
+        }
+
+        Ok(checksum)
+    }
+
+    /// The rows without exactly one pair.
+    pub fn flagged(&self) -> impl Iterator<Item = &(usize, RowPairs)> {
+        self.rows
+            .iter()
+            .filter(|(_, pairs)| !matches!(pairs, RowPairs::Unique(_)))
+    }
+}
+
+/// Every pair of values in `row` where one divides the other, in column order. Zero divides
+/// nothing.
+pub fn divisible_pairs(row: &[u32]) -> Vec<PairMatch> {
+    PairSearch::Auto.find(row)
+}
+
+impl PairSearch {
+    pub fn find(self, row: &[u32]) -> Vec<PairMatch> {
+        match self {
+            PairSearch::Pairwise => pairwise(row),
+            PairSearch::Multiples => multiples(row),
+            PairSearch::Auto => {
+                let n = row.len() as u64;
+                let max = row.iter().copied().max().unwrap_or(0);
+                let multiples_steps: u64 = row
+                    .iter()
+                    .filter(|&&value| value != 0)
+                    .map(|&value| u64::from(max / value))
+                    .sum();
+
+                if multiples_steps < n * n.saturating_sub(1) / 2 {
+                    multiples(row)
+                } else {
+                    pairwise(row)
+                }
+            }
+        }
+    }
+}
+
+fn pairwise(row: &[u32]) -> Vec<PairMatch> {
+    let cells = row
+        .iter()
+        .enumerate()
+        .map(|(column, &value)| Cell { column, value });
+
+    cells
+        .tuple_combinations()
+        .filter_map(|(a, b)| {
+            let (dividend, divisor) = if a.value >= b.value { (a, b) } else { (b, a) };
+            (divisor.value != 0 && dividend.value % divisor.value == 0)
+                .then_some(PairMatch { dividend, divisor })
+        })
+        .collect()
+}
+
+fn multiples(row: &[u32]) -> Vec<PairMatch> {
+    let mut columns: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (column, &value) in row.iter().enumerate().filter(|(_, &value)| value != 0) {
+        columns.entry(value).or_default().push(column);
+    }
+    let max = columns.keys().copied().max().map_or(0, u64::from);
+
+    let mut pairs = Vec::new();
+    for (&value, divisors) in &columns {
+        // Equal values divide each other, and `pairwise` takes the earlier one as the dividend
+        for (i, &dividend) in divisors.iter().enumerate() {
+            for &divisor in &divisors[i + 1..] {
+                pairs.push(PairMatch {
+                    dividend: Cell {
+                        column: dividend,
+                        value,
+                    },
+                    divisor: Cell {
+                        column: divisor,
+                        value,
+                    },
+                });
+            }
+        }
+
+        for multiple in (2 * u64::from(value)..=max).step_by(value as usize) {
+            let multiple = multiple as u32;
+            for &dividend in columns.get(&multiple).into_iter().flatten() {
+                for &divisor in divisors {
+                    pairs.push(PairMatch {
+                        dividend: Cell {
+                            column: dividend,
+                            value: multiple,
+                        },
+                        divisor: Cell {
+                            column: divisor,
+                            value,
+                        },
+                    });
+                }
+            }
+        }
+    }
+
+    pairs.sort_unstable_by_key(|pair| {
+        let (a, b) = (pair.dividend.column, pair.divisor.column);
+        (a.min(b), a.max(b))
+    });
+    pairs
+}
+
+impl RowReducer for PairwiseGcdSum {
+    fn reduce(&self, row: &[u32]) -> u64 {
+        row.iter()
             .tuple_combinations()
-            .find(|(a, b)| a % b == 0)
-            .map(|(a, b)| a / b)
-            .unwrap();
+            .map(|(&a, &b)| u64::from(gcd(a, b)))
+            .sum()
     }
+}
 
-    checksum
+impl RowReducer for MedianSpread {
+    fn reduce(&self, row: &[u32]) -> u64 {
+        if row.is_empty() {
+            return 0;
+        }
+
+        let sorted: Vec<u32> = row.iter().copied().sorted().collect();
+        let median = sorted[(sorted.len() - 1) / 2];
+
+        u64::from((median - sorted[0]).max(sorted[sorted.len() - 1] - median))
+    }
+}
+
+fn parse_row(line_no: usize, line: &str) -> Result<Vec<u32>> {
+    let cells: Vec<&str> = if line.contains(',') {
+        line.split(',').map(str::trim).collect()
+    } else {
+        line.split_whitespace().collect()
+    };
+
+    cells
+        .into_iter()
+        .map(|cell| {
+            if cell.is_empty() {
+                return Err(ParseError::at_token(line_no, line, cell, "empty cell").into());
+            }
+
+            cell.parse::<u32>().map_err(|e| {
+                ParseError::at_token(line_no, line, cell, format!("{:?}: {}", cell, e)).into()
+            })
+        })
+        .collect()
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
+    use proptest::prelude::*;
 
     #[test]
     fn part_1_works() {
@@ -48,7 +417,7 @@ mod tests {
             7 5 3
             2 4 6 8";
 
-        let checksum = part_1(input);
+        let checksum = part_1(&Spreadsheet::parse(input).unwrap());
 
         assert_eq!(18, checksum);
     }
@@ -60,8 +429,135 @@ mod tests {
             9 4 7 3
             3 8 6 5";
 
-        let checksum = part_2(input);
+        let checksum = part_2(&Spreadsheet::parse(input).unwrap()).unwrap();
 
         assert_eq!(9, checksum);
     }
+
+    #[test]
+    fn divisible_pairs_are_reported() {
+        let spreadsheet = Spreadsheet::parse("5 9 2 8\n\n3 5 7\n2 4 8").unwrap();
+        let report = spreadsheet.divisible_pairs();
+
+        let cell = |column, value| Cell { column, value };
+        let pair = |dividend, divisor| PairMatch { dividend, divisor };
+        assert_eq!(
+            vec![
+                (1, RowPairs::Unique(pair(cell(3, 8), cell(2, 2)))),
+                (3, RowPairs::Missing),
+                (
+                    4,
+                    RowPairs::Ambiguous(vec![
+                        pair(cell(1, 4), cell(0, 2)),
+                        pair(cell(2, 8), cell(0, 2)),
+                        pair(cell(2, 8), cell(1, 4)),
+                    ])
+                ),
+            ],
+            report.rows
+        );
+
+        assert_eq!(
+            vec![3, 4],
+            report.flagged().map(|(line, _)| *line).collect_vec()
+        );
+        assert_eq!(6, report.checksum());
+        assert_eq!(
+            Err(Error::DivisiblePairs {
+                line: 3,
+                pairs: RowPairs::Missing
+            }),
+            report.strict_checksum()
+        );
+        assert_eq!(
+            "Line 3: no evenly divisible pair",
+            report.strict_checksum().unwrap_err().to_string()
+        );
+
+        let report = Spreadsheet::parse("2 4 8").unwrap().divisible_pairs();
+        assert!(matches!(
+            report.strict_checksum(),
+            Err(Error::DivisiblePairs {
+                line: 1,
+                pairs: RowPairs::Ambiguous(_)
+            })
+        ));
+        assert_eq!(
+            "Line 1: 3 evenly divisible pairs, in columns 1/0, 2/0, 2/1",
+            report.strict_checksum().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn separators_are_interchangeable() {
+        let expected = Spreadsheet::parse("5 1 9 5\n7 5 3").unwrap();
+
+        assert_eq!(expected, Spreadsheet::parse("5\t1\t9\t5\n7\t5 3").unwrap());
+        assert_eq!(expected, Spreadsheet::parse("5,1,9,5\n7, 5, 3\n").unwrap());
+    }
+
+    #[test]
+    fn checksums_works() {
+        let spreadsheet = Spreadsheet::parse("5 9 2 8\n9 4 7 3\n3 8 6 5").unwrap();
+        let row_count = |_: &[u32]| 1;
+
+        let checksums = spreadsheet.checksums(&[
+            &MinMaxSpread,
+            &DivisiblePair,
+            &PairwiseGcdSum,
+            &MedianSpread,
+            &row_count,
+        ]);
+
+        assert_eq!(vec![18, 9, 24, 12, 3], checksums);
+    }
+
+    #[test]
+    fn pair_searches_agree() {
+        let rows: [&[u32]; 6] = [
+            &[],
+            &[5, 9, 2, 8],
+            &[3, 5, 7],
+            &[2, 4, 8, 2, 0, 16, 4],
+            &[0, 0, 1, 7, 7],
+            &[12, 6, 4, 3, 2, 1, 24, 7, 14, 49],
+        ];
+
+        for row in rows {
+            let expected = PairSearch::Pairwise.find(row);
+
+            assert_eq!(expected, PairSearch::Multiples.find(row), "{:?}", row);
+            assert_eq!(expected, PairSearch::Auto.find(row), "{:?}", row);
+        }
+
+        // Far too many multiples to step through, so this has to be searched pairwise
+        let row = [1, u32::MAX, u32::MAX];
+        assert_eq!(PairSearch::Pairwise.find(&row), PairSearch::Auto.find(&row));
+    }
+
+    proptest! {
+        #[test]
+        fn multiples_matches_pairwise(row in prop::collection::vec(0u32..200, 0..40)) {
+            prop_assert_eq!(PairSearch::Pairwise.find(&row), PairSearch::Multiples.find(&row));
+        }
+    }
+
+    #[test]
+    fn invalid_cell_is_reported() {
+        let input = "5 1 9 5\n7 x 3";
+        let err = Spreadsheet::parse(input).unwrap_err();
+
+        assert_eq!(
+            Error::Parse(ParseError::new(
+                2,
+                3,
+                "\"x\": invalid digit found in string"
+            )),
+            err
+        );
+
+        let err = Spreadsheet::parse("5,1,,5").unwrap_err();
+
+        assert_eq!(Error::Parse(ParseError::new(1, 5, "empty cell")), err);
+    }
 }
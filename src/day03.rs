@@ -1,125 +1,718 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use itertools::Itertools;
+
+use num_bigint::BigUint;
+
+use crate::budget::Budget;
+use crate::error::{ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Input = i32;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(part_1(*input))
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(part_2(*input, &mut Budget::from_defaults())?)
+    }
+}
+
+/// The square to start from. Any positive `i32` is accepted, and both parts solve all of them.
+pub fn parse_input(input: &str) -> Result<i32> {
+    let trimmed = input.trim();
+
+    match trimmed.parse::<i32>() {
+        Ok(value) if value >= 1 => Ok(value),
+        Ok(_) => Err(ParseError::at_token(1, input, trimmed, "square must be at least 1").into()),
+        Err(e) => {
+            Err(ParseError::at_token(1, input, trimmed, format!("{:?}: {}", trimmed, e)).into())
+        }
+    }
+}
 
 /// 17 16 15 14 13
 /// 18  5  4  3 12
 /// 19  6  1  2 11
 /// 20  7  8  9 10
 /// 21 22 23 24 25
-pub fn part_1(input: &str) -> i32 {
-    let value: i32 = input.parse().unwrap();
+pub fn part_1(value: i32) -> u64 {
+    Spiral::index_to_coord(value as u64).manhattan()
+}
 
-    let (ring, square) = get_nearest_square(value);
+pub fn part_2(value: i32, budget: &mut Budget) -> Result<u64> {
+    for sum in Spiral::neighbour_sums::<u64>(Neighbourhood::Moore) {
+        budget.tick()?;
+        if sum > value as u64 {
+            return Ok(sum);
+        }
+    }
 
-    // to generate arbitrary sequence 0,1,2..n-1,n,n-1,..2,1,0,1,2.. we can use the formula
-    // y = -|x-n-2n.floor(x/2n)| + n
-    let x = square - value;
-    let n = ring;
-    let adjustment = -(x - n - 2 * n * x.div_euclid(2 * n)).abs() + n;
+    unreachable!("an i32 is exceeded long before the sums overflow a u64")
+}
 
-    2 * ring - adjustment
+/// Which squares count as neighbours when summing them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// All eight surrounding squares, as in part 2
+    Moore,
+    /// Only the four squares sharing a side
+    VonNeumann,
 }
 
-// takes a value and calculates the nearest perfect square >= the value and the ring in which it
-// can be found
-fn get_nearest_square(value: i32) -> (i32, i32) {
-    (1..)
-        .step_by(2)
-        .map(|n| n * n)
-        .enumerate()
-        .find(|&(_, sq)| sq >= value)
-        .map(|(i, sq)| (i as i32, sq))
-        .unwrap()
+impl Neighbourhood {
+    pub fn offsets(self) -> &'static [(i64, i64)] {
+        match self {
+            Neighbourhood::Moore => &[
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+                (-1, 0),
+                (1, 0),
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+            ],
+            Neighbourhood::VonNeumann => &[(0, 1), (-1, 0), (1, 0), (0, -1)],
+        }
+    }
 }
 
-pub fn part_2(input: &str) -> Option<i32> {
-    let value: i32 = input.parse().unwrap();
+/// A type the neighbour sums can be computed in.
+pub trait SumValue: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// `None` if the sum doesn't fit.
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+}
 
-    let mut pos = (0, 0);
-    let mut spiral: HashMap<(isize, isize), i32> = HashMap::new();
+macro_rules! impl_sum_value {
+    ($($t:ty),*) => {
+        $(impl SumValue for $t {
+            fn zero() -> Self {
+                0
+            }
 
-    spiral.insert(pos, 1);
-    pos.0 += 1;
+            fn one() -> Self {
+                1
+            }
 
-    for i in (3..).step_by(2) {
-        for _ in 0..i - 2 {
-            let new = set_value_from_surrounding(pos, &mut spiral);
-            if new > value {
-                return Some(new);
+            fn checked_add(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_add(*self, *other)
             }
+        })*
+    };
+}
+
+impl_sum_value!(u32, u64, u128);
+
+impl SumValue for BigUint {
+    fn zero() -> Self {
+        BigUint::ZERO
+    }
+
+    fn one() -> Self {
+        BigUint::from(1u8)
+    }
 
-            pos.1 += 1;
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+}
+
+/// The value written to each square in spiral order when every square holds the sum of its
+/// neighbours written so far, starting from 1. With the Moore neighbourhood this is OEIS A141481.
+/// Ends if a sum no longer fits in `T`, which never happens for `BigUint`.
+#[derive(Debug, Clone)]
+pub struct NeighbourSums<T> {
+    neighbourhood: Neighbourhood,
+    squares: SpiralIter,
+    /// The sums of the previous ring and of the current one so far, indexed by offset into the
+    /// ring. A square's neighbours that have already been written are all in one or the other.
+    previous: Vec<T>,
+    current: Vec<T>,
+    overflowed: bool,
+}
+
+impl<T> NeighbourSums<T> {
+    /// The sum written to `index`, which must be in `ring` or the one before it.
+    fn written(&self, ring: u64, index: u64) -> &T {
+        let (values, ring) = if Spiral::ring(index) == ring {
+            (&self.current, ring)
+        } else {
+            (&self.previous, ring - 1)
+        };
+
+        &values[(index - Spiral::ring_range(ring).start()) as usize]
+    }
+}
+
+impl<T: SumValue> Iterator for NeighbourSums<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.overflowed {
+            return None;
         }
-        for _ in 0..i - 1 {
-            let new = set_value_from_surrounding(pos, &mut spiral);
-            if new > value {
-                return Some(new);
-            }
 
-            pos.0 -= 1;
+        let (index, coord) = self.squares.next()?;
+        let ring = Spiral::ring(index);
+        if index == *Spiral::ring_range(ring).start() {
+            self.previous = std::mem::take(&mut self.current);
         }
-        for _ in 0..i - 1 {
-            let new = set_value_from_surrounding(pos, &mut spiral);
-            if new > value {
-                return Some(new);
+
+        let sum = if index == 1 {
+            T::one()
+        } else {
+            let mut sum = T::zero();
+            for (dx, dy) in self.neighbourhood.offsets() {
+                let neighbour = Spiral::coord_to_index(coord.x + dx, coord.y + dy);
+                if neighbour < index {
+                    match sum.checked_add(self.written(ring, neighbour)) {
+                        Some(next) => sum = next,
+                        None => {
+                            self.overflowed = true;
+                            return None;
+                        }
+                    }
+                }
             }
+            sum
+        };
+
+        self.current.push(sum.clone());
+        Some(sum)
+    }
+}
+
+/// Indexes and coordinates of squares in spiral memory, as laid out in the diagram on `part_1`.
+/// Square 1 is at the origin and `y` increases upwards, so square 2 is at `(1, 0)` and square 3 at
+/// `(1, 1)`. Indexes start at 1.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Spiral;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Coord {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// The side of a ring a square is on, in the order the spiral visits them. Corners belong to the
+/// side that ends in them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Side {
+    Right,
+    Top,
+    Left,
+    Bottom,
+}
+
+/// Every square in spiral order, from square 1.
+#[derive(Debug, Clone)]
+pub struct SpiralIter {
+    index: u64,
+    coord: Coord,
+}
+
+impl Spiral {
+    /// The ring `index` is on, where square 1 is ring 0 and ring `r` ends with square `(2r + 1)²`.
+    pub fn ring(index: u64) -> u64 {
+        assert!(index >= 1, "Spiral indexes start at 1");
+        (index - 1).isqrt().div_ceil(2)
+    }
+
+    /// The indexes of the squares in `ring`.
+    pub fn ring_range(ring: u64) -> RangeInclusive<u64> {
+        match ring {
+            0 => 1..=1,
+            _ => (2 * ring - 1).pow(2) + 1..=(2 * ring + 1).pow(2),
+        }
+    }
+
+    pub fn ring_len(ring: u64) -> u64 {
+        match ring {
+            0 => 1,
+            _ => 8 * ring,
+        }
+    }
+
+    /// The side of its ring `index` is on, or `None` for square 1.
+    pub fn side(index: u64) -> Option<Side> {
+        let (_, side, _) = Self::position(index)?;
+        Some(side)
+    }
+
+    pub fn index_to_coord(index: u64) -> Coord {
+        let Some((ring, side, step)) = Self::position(index) else {
+            return Coord::ORIGIN;
+        };
+        let (r, t) = (ring as i64, step as i64);
 
-            pos.1 -= 1;
+        match side {
+            Side::Right => Coord::new(r, t + 1 - r),
+            Side::Top => Coord::new(r - 1 - t, r),
+            Side::Left => Coord::new(-r, r - 1 - t),
+            Side::Bottom => Coord::new(t + 1 - r, -r),
         }
-        for _ in 0..i {
-            let new = set_value_from_surrounding(pos, &mut spiral);
-            if new > value {
-                return Some(new);
+    }
+
+    pub fn coord_to_index(x: i64, y: i64) -> u64 {
+        let r = x.abs().max(y.abs());
+        if r == 0 {
+            return 1;
+        }
+
+        let (side, step) = if x == r && y > -r {
+            (0, y + r - 1)
+        } else if y == r {
+            (1, r - 1 - x)
+        } else if x == -r {
+            (2, r - 1 - y)
+        } else {
+            (3, x + r - 1)
+        };
+
+        let ring = r as u64;
+        Self::ring_range(ring).start() + side * 2 * ring + step as u64
+    }
+
+    pub fn iter() -> SpiralIter {
+        SpiralIter {
+            index: 1,
+            coord: Coord::ORIGIN,
+        }
+    }
+
+    pub fn neighbour_sums<T: SumValue>(neighbourhood: Neighbourhood) -> NeighbourSums<T> {
+        NeighbourSums {
+            neighbourhood,
+            squares: Self::iter(),
+            previous: Vec::new(),
+            current: Vec::new(),
+            overflowed: false,
+        }
+    }
+
+    /// The squares within `radius` of square 1 labelled with their indexes, as in the diagram on
+    /// `part_1`.
+    pub fn render_ascii(radius: u64) -> String {
+        Self::render_ascii_values(radius, 1..)
+    }
+
+    /// The squares within `radius` of square 1 labelled with `values` in spiral order, e.g. those
+    /// from `Spiral::neighbour_sums`. Squares left over once `values` run out are blank.
+    pub fn render_ascii_values<T: Display>(
+        radius: u64,
+        values: impl IntoIterator<Item = T>,
+    ) -> String {
+        let r = radius as i64;
+        let labels: Vec<String> = values
+            .into_iter()
+            .take(Self::ring_range(radius).end().to_owned() as usize)
+            .map(|value| value.to_string())
+            .collect();
+        let width = labels.iter().map(String::len).max().unwrap_or(0);
+
+        let mut grid = String::new();
+        for y in (-r..=r).rev() {
+            let row = (-r..=r)
+                .map(|x| {
+                    let label = labels
+                        .get(Self::coord_to_index(x, y) as usize - 1)
+                        .map_or("", String::as_str);
+                    format!("{:>width$}", label, width = width)
+                })
+                .join(" ");
+
+            grid.push_str(row.trim_end());
+            grid.push('\n');
+        }
+
+        grid
+    }
+
+    /// A shortest path from `index` back to square 1, first along the row and then the column,
+    /// including both ends.
+    pub fn manhattan_path(index: u64) -> Vec<Coord> {
+        let Coord { mut x, mut y } = Self::index_to_coord(index);
+        let mut path = vec![Coord::new(x, y)];
+
+        while x != 0 {
+            x -= x.signum();
+            path.push(Coord::new(x, y));
+        }
+        while y != 0 {
+            y -= y.signum();
+            path.push(Coord::new(x, y));
+        }
+
+        path
+    }
+
+    /// An SVG of the squares within `radius` of square 1, widened to take in `from`, with the
+    /// Manhattan path from `from` to square 1 highlighted.
+    pub fn render_svg(radius: u64, from: u64) -> String {
+        const CELL: i64 = 40;
+
+        let r = radius.max(Self::ring(from)) as i64;
+        let size = (2 * r + 1) * CELL;
+        let path = Self::manhattan_path(from);
+        let on_path: HashSet<Coord> = path.iter().copied().collect();
+        // Square centres, with y flipped to point down the image
+        let centre = |coord: Coord| {
+            (
+                (coord.x + r) * CELL + CELL / 2,
+                (r - coord.y) * CELL + CELL / 2,
+            )
+        };
+
+        let mut svg = String::new();
+        svg.push_str(&format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" \
+             viewBox=\"0 0 {size} {size}\" font-family=\"monospace\" font-size=\"12\">\n"
+        ));
+
+        for y in (-r..=r).rev() {
+            for x in -r..=r {
+                let coord = Coord::new(x, y);
+                let (cx, cy) = centre(coord);
+                let fill = if on_path.contains(&coord) {
+                    "#ffd54f"
+                } else {
+                    "#ffffff"
+                };
+
+                svg.push_str(&format!(
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{CELL}\" height=\"{CELL}\" \
+                     fill=\"{fill}\" stroke=\"#999999\"/>\n",
+                    cx - CELL / 2,
+                    cy - CELL / 2,
+                ));
+                svg.push_str(&format!(
+                    "  <text x=\"{cx}\" y=\"{cy}\" text-anchor=\"middle\" \
+                     dominant-baseline=\"central\">{}</text>\n",
+                    Self::coord_to_index(x, y)
+                ));
             }
+        }
 
-            pos.0 += 1;
+        let points = path
+            .iter()
+            .map(|&coord| {
+                let (x, y) = centre(coord);
+                format!("{},{}", x, y)
+            })
+            .join(" ");
+        svg.push_str(&format!(
+            "  <polyline points=\"{points}\" fill=\"none\" stroke=\"#e53935\" \
+             stroke-width=\"3\"/>\n"
+        ));
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+
+    /// The ring, side and 0-based step along that side of `index`, unless it is square 1.
+    fn position(index: u64) -> Option<(u64, Side, u64)> {
+        let ring = Self::ring(index);
+        if ring == 0 {
+            return None;
         }
+
+        let offset = index - Self::ring_range(ring).start();
+        let side = match offset / (2 * ring) {
+            0 => Side::Right,
+            1 => Side::Top,
+            2 => Side::Left,
+            _ => Side::Bottom,
+        };
+
+        Some((ring, side, offset % (2 * ring)))
     }
+}
 
-    None
+impl Coord {
+    pub const ORIGIN: Coord = Coord { x: 0, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// The number of steps back to square 1.
+    pub fn manhattan(&self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
 }
 
-const ADJACENT: [(isize, isize); 8] = [
-    (-1, 1),
-    (0, 1),
-    (1, 1),
-    (-1, 0),
-    (1, 0),
-    (-1, -1),
-    (0, -1),
-    (1, -1),
-];
+impl Iterator for SpiralIter {
+    type Item = (u64, Coord);
 
-fn set_value_from_surrounding(
-    pos: (isize, isize),
-    spiral: &mut HashMap<(isize, isize), i32>,
-) -> i32 {
-    let sum: i32 = ADJACENT
-        .iter()
-        .filter_map(|&adj| spiral.get(&(pos.0 + adj.0, pos.1 + adj.1)))
-        .sum();
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = (self.index, self.coord);
 
-    spiral.insert(pos, sum);
+        // Walk up the right side, left along the top, down the left side, then right along the
+        // bottom and out past the corner into the next ring
+        let Coord { x, y } = self.coord;
+        let r = x.abs().max(y.abs());
+        self.coord = if x == r && y > -r && y < r {
+            Coord::new(x, y + 1)
+        } else if y == r && x > -r {
+            Coord::new(x - 1, y)
+        } else if x == -r && y > -r {
+            Coord::new(x, y - 1)
+        } else {
+            Coord::new(x + 1, y)
+        };
+        self.index += 1;
 
-    sum
+        Some(item)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::budget::Limits;
+    use crate::error::Error;
+    use proptest::prelude::*;
 
     #[test]
-    fn get_ring_works() {
-        assert_eq!((1, 9), get_nearest_square(8));
-        assert_eq!((2, 25), get_nearest_square(10));
-        assert_eq!((2, 25), get_nearest_square(25));
+    fn part_1_works() {
+        assert_eq!(0, part_1(1));
+        assert_eq!(6, part_1(49));
+        assert_eq!(4, part_1(25));
+        assert_eq!(2, part_1(23));
+        assert_eq!(3, part_1(12));
+        assert_eq!(31, part_1(1024));
+        assert_eq!(46_339, part_1(2_147_395_600));
+        assert_eq!(41_706, part_1(i32::MAX));
     }
 
     #[test]
-    fn part_1_works() {
-        assert_eq!(6, part_1("49"));
-        assert_eq!(4, part_1("25"));
-        assert_eq!(2, part_1("23"));
-        assert_eq!(3, part_1("12"));
-        assert_eq!(31, part_1("1024"));
+    fn every_accepted_square_is_solved() {
+        for input in ["1", "2", "2147395600", "2147483647\n"] {
+            let square = parse_input(input).unwrap();
+            assert_eq!(
+                Spiral::manhattan_path(square as u64).len() as u64 - 1,
+                part_1(square)
+            );
+            assert!(part_2(square, &mut Budget::unlimited()).unwrap() > square as u64);
+        }
+
+        for input in ["0", "-5", "2147483648", "twelve"] {
+            assert!(matches!(parse_input(input), Err(Error::Parse(_))));
+        }
+    }
+
+    #[test]
+    fn spiral_matches_diagram() {
+        let diagram = [
+            [17, 16, 15, 14, 13],
+            [18, 5, 4, 3, 12],
+            [19, 6, 1, 2, 11],
+            [20, 7, 8, 9, 10],
+            [21, 22, 23, 24, 25],
+        ];
+
+        for (row, y) in diagram.iter().zip((-2..=2).rev()) {
+            for (&index, x) in row.iter().zip(-2..=2) {
+                assert_eq!(index, Spiral::coord_to_index(x, y));
+                assert_eq!(Coord::new(x, y), Spiral::index_to_coord(index));
+            }
+        }
+    }
+
+    #[test]
+    fn rings_and_sides_work() {
+        assert_eq!(0, Spiral::ring(1));
+        assert_eq!(1, Spiral::ring(2));
+        assert_eq!(1, Spiral::ring(9));
+        assert_eq!(2, Spiral::ring(10));
+        assert_eq!(10..=25, Spiral::ring_range(2));
+        assert_eq!(16, Spiral::ring_len(2));
+
+        let sides: Vec<_> = (10..=25).map(|i| Spiral::side(i).unwrap()).collect();
+        let expected = [Side::Right, Side::Top, Side::Left, Side::Bottom].map(|side| [side; 4]);
+        assert_eq!(expected.concat(), sides);
+        assert_eq!(None, Spiral::side(1));
+    }
+
+    proptest! {
+        #[test]
+        fn coord_to_index_inverts_index_to_coord(index in 1u64..1 << 40) {
+            let Coord { x, y } = Spiral::index_to_coord(index);
+            prop_assert_eq!(index, Spiral::coord_to_index(x, y));
+        }
+
+        #[test]
+        fn index_to_coord_inverts_coord_to_index(
+            x in -1i64 << 20..1 << 20,
+            y in -1i64 << 20..1 << 20,
+        ) {
+            let index = Spiral::coord_to_index(x, y);
+            prop_assert_eq!(Coord::new(x, y), Spiral::index_to_coord(index));
+            prop_assert_eq!(x.abs().max(y.abs()) as u64, Spiral::ring(index));
+            prop_assert!(Spiral::ring_range(Spiral::ring(index)).contains(&index));
+        }
+
+        #[test]
+        fn part_1_is_the_distance_to_the_nearest_side_midpoint(index in 1i32..=i32::MAX) {
+            // The midpoints of ring r's sides are r steps from square 1, and each step along a
+            // side away from them adds one more
+            let r = Spiral::ring(index as u64);
+            let distance = (0..4)
+                .map(|k| Spiral::ring_range(r).end() - r - 2 * r * k)
+                .map(|midpoint| midpoint.abs_diff(index as u64))
+                .min()
+                .unwrap();
+
+            prop_assert_eq!(r + distance, part_1(index));
+        }
+    }
+
+    #[test]
+    fn iter_walks_the_spiral() {
+        for (index, coord) in Spiral::iter().take(10_000) {
+            assert_eq!(Spiral::index_to_coord(index), coord);
+        }
+
+        // Each step moves to a neighbouring square
+        for ((_, a), (_, b)) in Spiral::iter().tuple_windows().take(10_000) {
+            assert_eq!(1, (a.x - b.x).abs() + (a.y - b.y).abs());
+        }
+    }
+
+    #[test]
+    fn render_ascii_works() {
+        let expected = "\
+17 16 15 14 13
+18  5  4  3 12
+19  6  1  2 11
+20  7  8  9 10
+21 22 23 24 25
+";
+        assert_eq!(expected, Spiral::render_ascii(2));
+        assert_eq!("1\n", Spiral::render_ascii(0));
+
+        let expected = " 5  4  2\n10  1  1\n11 23 25\n";
+        let sums = Spiral::neighbour_sums::<u64>(Neighbourhood::Moore);
+        assert_eq!(expected, Spiral::render_ascii_values(1, sums));
+
+        // Squares after the last value are left blank
+        assert_eq!("  4 3\n  1 2\n\n", Spiral::render_ascii_values(1, 1..=4));
+    }
+
+    #[test]
+    fn manhattan_path_works() {
+        assert_eq!(vec![Coord::ORIGIN], Spiral::manhattan_path(1));
+        assert_eq!(
+            vec![
+                Coord::new(-2, 2),
+                Coord::new(-1, 2),
+                Coord::new(0, 2),
+                Coord::new(0, 1),
+                Coord::ORIGIN
+            ],
+            Spiral::manhattan_path(17)
+        );
+
+        for index in [2, 12, 23, 1024] {
+            assert_eq!(
+                part_1(index as i32),
+                Spiral::manhattan_path(index).len() as u64 - 1
+            );
+        }
+    }
+
+    #[test]
+    fn render_svg_works() {
+        let svg = Spiral::render_svg(1, 12);
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // Widened to ring 2 to take in square 12
+        assert_eq!(25, svg.matches("<rect ").count());
+        assert_eq!(4, svg.matches("fill=\"#ffd54f\"").count());
+        assert!(svg.contains("<polyline points=\"180,60 140,60 100,60 100,100\""));
+    }
+
+    #[test]
+    fn part_2_works() {
+        let mut budget = Budget::unlimited();
+
+        assert_eq!(Ok(2), part_2(1, &mut budget));
+        assert_eq!(Ok(362), part_2(351, &mut budget));
+        assert_eq!(Ok(806), part_2(747, &mut budget));
+    }
+
+    #[test]
+    fn neighbour_sums_work() {
+        let expected: [u64; 23] = [
+            1, 1, 2, 4, 5, 10, 11, 23, 25, 26, 54, 57, 59, 122, 133, 142, 147, 304, 330, 351, 362,
+            747, 806,
+        ];
+        let sums: Vec<u64> = Spiral::neighbour_sums(Neighbourhood::Moore)
+            .take(23)
+            .collect();
+        assert_eq!(expected.to_vec(), sums);
+
+        let sums: Vec<u64> = Spiral::neighbour_sums(Neighbourhood::VonNeumann)
+            .take(10)
+            .collect();
+        assert_eq!(vec![1, 1, 1, 2, 2, 3, 3, 4, 5, 5], sums);
+    }
+
+    #[test]
+    fn neighbour_sums_stop_on_overflow() {
+        let narrow: Vec<u128> = Spiral::neighbour_sums(Neighbourhood::Moore).collect();
+        let wide: Vec<BigUint> = Spiral::neighbour_sums(Neighbourhood::Moore)
+            .take(narrow.len() + 10)
+            .collect();
+
+        assert!(narrow.len() > 100);
+        for (narrow, wide) in narrow.iter().zip(&wide) {
+            assert_eq!(&BigUint::from(*narrow), wide);
+        }
+        assert!(wide[narrow.len()] > BigUint::from(u128::MAX));
+    }
+
+    #[test]
+    fn neighbour_sums_only_keep_two_rings() {
+        let mut sums = Spiral::neighbour_sums::<BigUint>(Neighbourhood::Moore);
+        sums.nth(1999).unwrap();
+
+        let ring = Spiral::ring(2000);
+        assert_eq!(Spiral::ring_len(ring - 1) as usize, sums.previous.len());
+        assert_eq!(
+            (2000 - Spiral::ring_range(ring).start() + 1) as usize,
+            sums.current.len()
+        );
+    }
+
+    #[test]
+    fn part_2_gives_up_when_out_of_budget() {
+        let mut budget = Budget::new(Limits {
+            max_iterations: Some(10),
+            timeout: None,
+        });
+
+        assert!(matches!(
+            part_2(747, &mut budget),
+            Err(Error::BudgetExhausted(_))
+        ));
     }
 }
@@ -1,46 +1,487 @@
-use std::collections::HashSet;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
 
 use itertools::Itertools;
+use unicode_normalization::UnicodeNormalization;
 
-struct Passphrase {
-    inner: HashSet<String>,
+use crate::solution::Solution;
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(input.to_owned())
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(part_1(input))
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(part_2(input))
+    }
+}
+
+/// What makes two words in a passphrase collide.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// The same word appears twice, as in part 1
+    #[default]
+    Exact,
+    /// One word is an anagram of another, as in part 2
+    Anagram,
+}
+
+/// A Unicode normalisation form applied to words before comparing them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Normalization {
+    Nfc,
+    Nfkc,
+}
+
+/// The rules a passphrase must follow. The default is part 1's, and the others are combined with
+/// the builder methods, e.g. `Policy::new(Rule::Anagram).case_insensitive(true).min_words(3)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Policy {
+    pub rule: Rule,
+    pub case_insensitive: bool,
+    pub normalization: Option<Normalization>,
+    pub min_words: usize,
+    /// Words that may not appear at all, compared the same way as the words of a passphrase
+    pub forbidden: Vec<String>,
 }
 
-enum ChunkRule {
-    None,
-    Sorted,
+/// A word within a line. `position` is its 0-based index among the words and `column` the
+/// 1-based column it starts at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    pub position: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+/// A word that collides with an earlier one. `rule` is `Exact` for repeated words even when
+/// checking for anagrams.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+    pub first: Word,
+    pub repeat: Word,
+    pub rule: Rule,
+}
+
+/// A line of input checked against a policy, with everything that makes it invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Passphrase {
+    pub line: usize,
+    pub word_count: usize,
+    pub collisions: Vec<Collision>,
+    pub forbidden: Vec<Word>,
+    /// Set if the passphrase has fewer words than the policy's minimum, which is kept here
+    pub too_short: Option<usize>,
+}
+
+/// How words compare under `Rule::Anagram`: how many times each letter appears.
+type Signature = BTreeMap<char, u32>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Exact(String),
+    Anagram(Signature),
+}
+
+impl Policy {
+    pub fn new(rule: Rule) -> Self {
+        Self {
+            rule,
+            ..Self::default()
+        }
+    }
+
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    pub fn normalized(mut self, normalization: Option<Normalization>) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    pub fn min_words(mut self, min_words: usize) -> Self {
+        self.min_words = min_words;
+        self
+    }
+
+    pub fn forbid<S: Into<String>>(mut self, words: impl IntoIterator<Item = S>) -> Self {
+        self.forbidden.extend(words.into_iter().map(Into::into));
+        self
+    }
+
+    /// `word` as it is compared: normalised, then lowercased if case doesn't matter.
+    fn canonical(&self, word: &str) -> String {
+        let word: String = match self.normalization {
+            None => word.to_owned(),
+            Some(Normalization::Nfc) => word.nfc().collect(),
+            Some(Normalization::Nfkc) => word.nfkc().collect(),
+        };
+
+        if self.case_insensitive {
+            word.to_lowercase()
+        } else {
+            word
+        }
+    }
+
+    fn canonical_forbidden(&self) -> HashSet<String> {
+        self.forbidden
+            .iter()
+            .map(|word| self.canonical(word))
+            .collect()
+    }
+
+    fn key(&self, canonical: &str) -> Key {
+        match self.rule {
+            Rule::Exact => Key::Exact(canonical.to_owned()),
+            Rule::Anagram => {
+                let mut signature = Signature::new();
+                for c in canonical.chars() {
+                    *signature.entry(c).or_default() += 1;
+                }
+                Key::Anagram(signature)
+            }
+        }
+    }
 }
 
 impl Passphrase {
-    fn construct(input: &str, rule: ChunkRule) -> Result<Self, ()> {
-        let mut passphrase = HashSet::new();
+    pub fn check(line_no: usize, line: &str, policy: &Policy) -> Self {
+        Self::check_with(line_no, line, policy, &policy.canonical_forbidden())
+    }
 
-        for chunk in input.trim().split_whitespace() {
-            let chunk = match rule {
-                ChunkRule::None => chunk.to_owned(),
-                ChunkRule::Sorted => chunk.chars().sorted().collect(),
+    /// Like `check`, with the policy's forbidden words already in canonical form so that checking
+    /// many lines only computes them once.
+    fn check_with(
+        line_no: usize,
+        line: &str,
+        policy: &Policy,
+        forbidden: &HashSet<String>,
+    ) -> Self {
+        // Each distinct key with the first word that had it, and that word's canonical form
+        let mut seen: HashMap<Key, (Word, String)> = HashMap::new();
+        let mut passphrase = Self {
+            line: line_no,
+            word_count: 0,
+            collisions: Vec::new(),
+            forbidden: Vec::new(),
+            too_short: None,
+        };
+
+        for (position, text) in line.split_whitespace().enumerate() {
+            let offset = text.as_ptr() as usize - line.as_ptr() as usize;
+            let word = Word {
+                position,
+                column: line[..offset].chars().count() + 1,
+                text: text.to_owned(),
             };
+            passphrase.word_count += 1;
+
+            let canonical = policy.canonical(text);
+            if forbidden.contains(&canonical) {
+                passphrase.forbidden.push(word.clone());
+            }
 
-            let already_present = !passphrase.insert(chunk);
-            if already_present {
-                return Err(());
+            match seen.entry(policy.key(&canonical)) {
+                Entry::Occupied(e) => {
+                    let (first, first_canonical) = e.get();
+                    passphrase.collisions.push(Collision {
+                        rule: if *first_canonical == canonical {
+                            Rule::Exact
+                        } else {
+                            Rule::Anagram
+                        },
+                        first: first.clone(),
+                        repeat: word,
+                    });
+                }
+                Entry::Vacant(e) => {
+                    e.insert((word, canonical));
+                }
             }
         }
 
-        Ok(Self { inner: passphrase })
+        if passphrase.word_count < policy.min_words {
+            passphrase.too_short = Some(policy.min_words);
+        }
+
+        passphrase
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.collisions.is_empty() && self.forbidden.is_empty() && self.too_short.is_none()
     }
 }
 
-pub fn part_1(input: &str) -> usize {
+/// Checks every line of `input`, including the valid ones.
+pub fn validate(input: &str, policy: &Policy) -> Vec<Passphrase> {
+    let forbidden = policy.canonical_forbidden();
+
     input
         .lines()
-        .filter_map(|line| Passphrase::construct(line, ChunkRule::None).ok())
+        .enumerate()
+        .map(|(i, line)| Passphrase::check_with(i + 1, line, policy, &forbidden))
+        .collect()
+}
+
+pub fn part_1(input: &str) -> usize {
+    validate(input, &Policy::new(Rule::Exact))
+        .iter()
+        .filter(|passphrase| passphrase.is_valid())
         .count()
 }
 
 pub fn part_2(input: &str) -> usize {
-    input
-        .lines()
-        .filter_map(|line| Passphrase::construct(line, ChunkRule::Sorted).ok())
+    validate(input, &Policy::new(Rule::Anagram))
+        .iter()
+        .filter(|passphrase| passphrase.is_valid())
         .count()
 }
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        match rule {
+            "exact" => Ok(Rule::Exact),
+            "anagram" => Ok(Rule::Anagram),
+            _ => Err(format!("rule must be exact or anagram, not {}", rule)),
+        }
+    }
+}
+
+impl FromStr for Normalization {
+    type Err = String;
+
+    fn from_str(normalization: &str) -> Result<Self, Self::Err> {
+        match normalization {
+            "nfc" => Ok(Normalization::Nfc),
+            "nfkc" => Ok(Normalization::Nfkc),
+            _ => Err(format!(
+                "normalization must be nfc or nfkc, not {}",
+                normalization
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Collision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let relation = match self.rule {
+            Rule::Exact => "repeats",
+            Rule::Anagram => "is an anagram of",
+        };
+
+        write!(
+            f,
+            "{:?} at column {} {} {:?} at column {}",
+            self.repeat.text, self.repeat.column, relation, self.first.text, self.first.column
+        )
+    }
+}
+
+impl fmt::Display for Passphrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "Line {}: valid", self.line);
+        }
+
+        let too_short = self.too_short.map(|min_words| {
+            format!(
+                "{} word(s) where at least {} are needed",
+                self.word_count, min_words
+            )
+        });
+        let forbidden = self
+            .forbidden
+            .iter()
+            .map(|word| format!("{:?} at column {} is forbidden", word.text, word.column));
+        let collisions = self.collisions.iter().map(Collision::to_string);
+
+        write!(
+            f,
+            "Line {}: {}",
+            self.line,
+            too_short
+                .into_iter()
+                .chain(forbidden)
+                .chain(collisions)
+                .join("; ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn is_valid(line: &str, rule: Rule) -> bool {
+        Passphrase::check(1, line, &Policy::new(rule)).is_valid()
+    }
+
+    #[test]
+    fn exact_rule_works() {
+        assert!(is_valid("aa bb cc dd ee", Rule::Exact));
+        assert!(!is_valid("aa bb cc dd aa", Rule::Exact));
+        assert!(is_valid("aa bb cc dd aaa", Rule::Exact));
+    }
+
+    #[test]
+    fn anagram_rule_works() {
+        assert!(is_valid("abcde fghij", Rule::Anagram));
+        assert!(!is_valid("abcde xyz ecdab", Rule::Anagram));
+        assert!(is_valid("a ab abc abd abf abj", Rule::Anagram));
+        assert!(is_valid("iiii oiii ooii oooi oooo", Rule::Anagram));
+        assert!(!is_valid("oiii ioii iioi iiio", Rule::Anagram));
+    }
+
+    #[test]
+    fn part_1_works() {
+        let input = "aa bb cc dd ee\naa bb cc dd aa\naa bb cc dd aaa";
+
+        assert_eq!(2, part_1(input));
+    }
+
+    #[test]
+    fn part_2_works() {
+        let input = "\
+abcde fghij
+abcde xyz ecdab
+a ab abc abd abf abj
+iiii oiii ooii oooi oooo
+oiii ioii iioi iiio";
+
+        assert_eq!(3, part_2(input));
+    }
+
+    /// Short words over a small alphabet, so that collisions are common.
+    fn words() -> impl Strategy<Value = Vec<String>> {
+        prop::collection::vec("[abC]{1,3}", 0..8)
+    }
+
+    proptest! {
+        #[test]
+        fn anagram_valid_implies_exact_valid(words in words()) {
+            let line = words.join(" ");
+
+            prop_assert!(!is_valid(&line, Rule::Anagram) || is_valid(&line, Rule::Exact));
+        }
+
+        #[test]
+        fn case_insensitive_valid_implies_case_sensitive_valid(words in words()) {
+            let line = words.join(" ");
+            let policy = Policy::new(Rule::Exact);
+
+            let sensitive = Passphrase::check(1, &line, &policy);
+            let insensitive = Passphrase::check(1, &line, &policy.case_insensitive(true));
+
+            prop_assert!(!insensitive.is_valid() || sensitive.is_valid());
+        }
+
+        #[test]
+        fn permuting_words_keeps_validity(
+            (words, shuffled) in words()
+                .prop_flat_map(|words| (Just(words.clone()), Just(words).prop_shuffle()))
+        ) {
+            let (line, shuffled) = (words.join(" "), shuffled.join(" "));
+
+            for rule in [Rule::Exact, Rule::Anagram] {
+                prop_assert_eq!(is_valid(&line, rule), is_valid(&shuffled, rule));
+            }
+        }
+
+        #[test]
+        fn each_repeat_collides_once(words in words()) {
+            let passphrase = Passphrase::check(1, &words.join(" "), &Policy::new(Rule::Exact));
+            let distinct = words.iter().collect::<HashSet<_>>().len();
+
+            prop_assert_eq!(words.len(), passphrase.word_count);
+            prop_assert_eq!(words.len() - distinct, passphrase.collisions.len());
+        }
+    }
+
+    #[test]
+    fn collisions_are_reported() {
+        let passphrase = Passphrase::check(3, "abc  bca xyz abc", &Policy::new(Rule::Anagram));
+
+        let word = |position, column, text: &str| Word {
+            position,
+            column,
+            text: text.to_owned(),
+        };
+        assert_eq!(
+            vec![
+                Collision {
+                    first: word(0, 1, "abc"),
+                    repeat: word(1, 6, "bca"),
+                    rule: Rule::Anagram,
+                },
+                Collision {
+                    first: word(0, 1, "abc"),
+                    repeat: word(3, 14, "abc"),
+                    rule: Rule::Exact,
+                },
+            ],
+            passphrase.collisions
+        );
+        assert_eq!(
+            "Line 3: \"bca\" at column 6 is an anagram of \"abc\" at column 1; \
+             \"abc\" at column 14 repeats \"abc\" at column 1",
+            passphrase.to_string()
+        );
+    }
+
+    #[test]
+    fn policies_combine() {
+        let policy = Policy::new(Rule::Anagram)
+            .case_insensitive(true)
+            .normalized(Some(Normalization::Nfkc))
+            .min_words(4)
+            .forbid(["PASSWORD"]);
+
+        // "ﬁ" is a ligature, which NFKC turns into "fi"
+        let passphrase = Passphrase::check(1, "Fine password ﬁne", &policy);
+        assert!(!passphrase.is_valid());
+        assert_eq!(
+            "Line 1: 3 word(s) where at least 4 are needed; \
+             \"password\" at column 6 is forbidden; \
+             \"ﬁne\" at column 15 repeats \"Fine\" at column 1",
+            passphrase.to_string()
+        );
+
+        let passphrase = Passphrase::check(1, "Listen silent enlist tinsel", &policy);
+        assert_eq!(3, passphrase.collisions.len());
+        assert!(passphrase
+            .collisions
+            .iter()
+            .all(|c| c.rule == Rule::Anagram));
+
+        // Composed and decomposed "é" only match once normalised
+        let (composed, decomposed) = ("caf\u{e9}", "cafe\u{301}");
+        let line = format!("{} {}", composed, decomposed);
+        assert!(Passphrase::check(1, &line, &Policy::default()).is_valid());
+        assert!(!Passphrase::check(
+            1,
+            &line,
+            &Policy::default().normalized(Some(Normalization::Nfc))
+        )
+        .is_valid());
+    }
+}
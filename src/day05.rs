@@ -1,21 +1,56 @@
-pub fn parse_input(input: &str) -> Vec<isize> {
+use crate::budget::Budget;
+use crate::error::{ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = Vec<isize>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(part_1(&mut input.clone(), &mut Budget::from_defaults())?)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(part_2(&mut input.clone(), &mut Budget::from_defaults())?)
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<isize>> {
     input
         .lines()
-        .filter_map(|line| line.trim().parse::<isize>().ok())
+        .enumerate()
+        .map(|(i, line)| (i, line, line.trim()))
+        .filter(|(_, _, jump)| !jump.is_empty())
+        .map(|(i, line, jump)| {
+            jump.parse::<isize>().map_err(|e| {
+                ParseError::at_token(i + 1, line, jump, format!("{:?}: {}", jump, e)).into()
+            })
+        })
         .collect()
 }
 
-pub fn part_1(jumps: &mut [isize]) -> usize {
+pub fn part_1(jumps: &mut [isize], budget: &mut Budget) -> Result<usize> {
     let mut pos: isize = 0;
     let mut num_jumps = 0;
 
     loop {
         if pos < 0 {
-            return num_jumps;
+            return Ok(num_jumps);
         }
 
+        budget.tick()?;
+
         match jumps.get_mut(pos as usize) {
-            None => return num_jumps,
+            None => return Ok(num_jumps),
             Some(jump) => {
                 pos += *jump;
                 *jump += 1;
@@ -26,17 +61,19 @@ pub fn part_1(jumps: &mut [isize]) -> usize {
     }
 }
 
-pub fn part_2(jumps: &mut [isize]) -> usize {
+pub fn part_2(jumps: &mut [isize], budget: &mut Budget) -> Result<usize> {
     let mut pos: isize = 0;
     let mut num_jumps = 0;
 
     loop {
         if pos < 0 {
-            return num_jumps;
+            return Ok(num_jumps);
         }
 
+        budget.tick()?;
+
         match jumps.get_mut(pos as usize) {
-            None => return num_jumps,
+            None => return Ok(num_jumps),
             Some(jump) => {
                 pos += *jump;
                 if *jump >= 3 {
@@ -54,6 +91,8 @@ pub fn part_2(jumps: &mut [isize]) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::budget::Limits;
+    use crate::error::Error;
 
     #[test]
     fn part_1_works() {
@@ -64,8 +103,8 @@ mod tests {
             1
            -3";
 
-        let mut input = parse_input(input);
-        let num_jumps = part_1(&mut input);
+        let mut input = parse_input(input).unwrap();
+        let num_jumps = part_1(&mut input, &mut Budget::unlimited()).unwrap();
 
         assert_eq!(5, num_jumps);
     }
@@ -79,9 +118,38 @@ mod tests {
             1
            -3";
 
-        let mut input = parse_input(input);
-        let num_jumps = part_2(&mut input);
+        let mut input = parse_input(input).unwrap();
+        let num_jumps = part_2(&mut input, &mut Budget::unlimited()).unwrap();
 
         assert_eq!(10, num_jumps);
     }
+
+    #[test]
+    fn long_runs_exhaust_the_budget() {
+        let mut input = parse_input("0\n3\n0\n1\n-3").unwrap();
+        let mut budget = Budget::new(Limits {
+            max_iterations: Some(9),
+            timeout: None,
+        });
+
+        assert!(matches!(
+            part_2(&mut input, &mut budget),
+            Err(Error::BudgetExhausted(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_jump_is_reported() {
+        let input = "0\n  3\n  +-1";
+        let err = parse_input(input).unwrap_err();
+
+        assert_eq!(
+            Error::Parse(ParseError::new(
+                3,
+                3,
+                "\"+-1\": invalid digit found in string"
+            )),
+            err
+        );
+    }
 }
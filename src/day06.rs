@@ -1,42 +1,70 @@
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 
-pub fn part_1<const N: usize>(input: &str) -> Option<usize> {
-    let mut memory_bank = MemoryBank::<N>::from(input);
+use crate::budget::Budget;
+use crate::error::{Error, ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    type Input = MemoryBank<16>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(MemoryBank::try_from(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(part_1(input, &mut Budget::from_defaults())?)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(part_2(input, &mut Budget::from_defaults())?)
+    }
+}
+
+pub fn part_1<const N: usize>(memory_bank: &MemoryBank<N>, budget: &mut Budget) -> Result<usize> {
+    let mut memory_bank = memory_bank.clone();
     let mut history = History::<N>::new();
     history.insert(memory_bank.clone());
 
     for i in 1.. {
+        budget.tick()?;
         memory_bank = memory_bank.redistribute();
         if !history.insert(memory_bank.clone()) {
-            return Some(i);
+            return Ok(i);
         }
     }
 
-    None
+    unreachable!()
 }
 
-pub fn part_2<const N: usize>(input: &str) -> Option<usize> {
-    let mut memory_bank = MemoryBank::<N>::from(input);
+pub fn part_2<const N: usize>(memory_bank: &MemoryBank<N>, budget: &mut Budget) -> Result<usize> {
+    let mut memory_bank = memory_bank.clone();
     let mut history = LoopHistory::<N>::new();
     history.insert(memory_bank.clone(), 0);
 
     for i in 1.. {
+        budget.tick()?;
         memory_bank = memory_bank.redistribute();
         match history.entry(memory_bank.clone()) {
-            Entry::Occupied(e) => return Some(i - *e.get()),
+            Entry::Occupied(e) => return Ok(i - *e.get()),
             Entry::Vacant(e) => {
                 e.insert(i);
             }
         }
     }
 
-    None
+    unreachable!()
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MemoryBank<const N: usize> {
-    buckets: [u8; N],
+    buckets: [u32; N],
 }
 
 type History<const N: usize> = HashSet<MemoryBank<N>>;
@@ -55,7 +83,7 @@ impl<const N: usize> MemoryBank<N> {
     pub fn redistribute(&self) -> Self {
         let mut next = self.clone();
 
-        let (mut i, mut max) = next
+        let (i, max) = next
             .buckets
             .iter()
             .copied()
@@ -66,37 +94,66 @@ impl<const N: usize> MemoryBank<N> {
 
         next.buckets[i] = 0;
 
-        while max > 0 {
-            i += 1;
-            i &= Self::MASK;
-            next.buckets[i] += 1;
-            max -= 1;
+        // Every bank gets a share of the blocks, then the remainder goes one each to the banks
+        // after this one
+        let (share, remainder) = (max / N as u32, max as usize % N);
+        for bucket in next.buckets.iter_mut() {
+            *bucket += share;
+        }
+        for offset in 1..=remainder {
+            next.buckets[(i + offset) & Self::MASK] += 1;
         }
 
         next
     }
 }
 
-impl<const N: usize> From<&str> for MemoryBank<N> {
-    fn from(input: &str) -> Self {
+impl<const N: usize> TryFrom<&str> for MemoryBank<N> {
+    type Error = Error;
+
+    fn try_from(input: &str) -> Result<Self> {
         let mut memory_bank = MemoryBank::new();
+        let mut count = 0;
+        // Redistributing never puts more than every block in one bank, so a total that fits can't
+        // overflow
+        let mut total: u32 = 0;
 
-        input
-            .split_whitespace()
-            .filter_map(|tok| tok.parse::<u8>().ok())
-            .enumerate()
-            .take(N)
-            .for_each(|(i, n)| {
-                memory_bank.buckets[i] = n;
-            });
+        for (line_no, line) in input.lines().enumerate().map(|(i, line)| (i + 1, line)) {
+            for tok in line.split_whitespace() {
+                let blocks = tok.parse::<u32>().map_err(|e| {
+                    ParseError::at_token(line_no, line, tok, format!("{:?}: {}", tok, e))
+                })?;
+                total = total.checked_add(blocks).ok_or_else(|| {
+                    let message = format!("more than {} blocks in total", u32::MAX);
+                    ParseError::at_token(line_no, line, tok, message)
+                })?;
+
+                if count == N {
+                    let message = format!("expected {} memory banks", N);
+                    return Err(ParseError::at_token(line_no, line, tok, message).into());
+                }
+
+                memory_bank.buckets[count] = blocks;
+                count += 1;
+            }
+        }
+
+        if count < N {
+            let line = input.lines().count().max(1);
+            let column = input.lines().last().map_or(0, |last| last.chars().count()) + 1;
+            let message = format!("expected {} memory banks, found {}", N, count);
+            return Err(ParseError::new(line, column, message).into());
+        }
 
-        memory_bank
+        Ok(memory_bank)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::budget::Limits;
+    use crate::error::{BudgetExhausted, Limit};
 
     #[test]
     fn redistribute_works() {
@@ -105,7 +162,7 @@ mod tests {
             buckets: [2, 4, 1, 2],
         };
 
-        let memory_bank = MemoryBank::<4>::from(input);
+        let memory_bank = MemoryBank::<4>::try_from(input).unwrap();
         let next = memory_bank.redistribute();
 
         assert_eq!(expected, next);
@@ -114,16 +171,92 @@ mod tests {
     #[test]
     fn part_1_works() {
         let input = "0 2 7 0";
-        let num_cycles = part_1::<4>(input);
+        let memory_bank = MemoryBank::<4>::try_from(input).unwrap();
+        let num_cycles = part_1(&memory_bank, &mut Budget::unlimited());
 
-        assert_eq!(Some(5), num_cycles);
+        assert_eq!(Ok(5), num_cycles);
     }
 
     #[test]
     fn part_2_works() {
         let input = "0 2 7 0";
-        let loop_length = part_2::<4>(input);
+        let memory_bank = MemoryBank::<4>::try_from(input).unwrap();
+        let loop_length = part_2(&memory_bank, &mut Budget::unlimited());
 
-        assert_eq!(Some(4), loop_length);
+        assert_eq!(Ok(4), loop_length);
+    }
+
+    #[test]
+    fn part_2_gives_up_when_out_of_budget() {
+        let memory_bank = MemoryBank::<4>::try_from("0 2 7 0").unwrap();
+        let mut budget = Budget::new(Limits {
+            max_iterations: Some(3),
+            timeout: None,
+        });
+
+        assert_eq!(
+            Err(Error::BudgetExhausted(BudgetExhausted {
+                iterations: 3,
+                limit: Limit::Iterations(3)
+            })),
+            part_2(&memory_bank, &mut budget)
+        );
+    }
+
+    #[test]
+    fn invalid_memory_bank_is_reported() {
+        let err = MemoryBank::<4>::try_from("0 2 x 0").unwrap_err();
+        assert_eq!(
+            Error::Parse(ParseError::new(
+                1,
+                5,
+                "\"x\": invalid digit found in string"
+            )),
+            err
+        );
+
+        let err = MemoryBank::<4>::try_from("0 2 7").unwrap_err();
+        assert_eq!(
+            Error::Parse(ParseError::new(1, 6, "expected 4 memory banks, found 3")),
+            err
+        );
+
+        let err = MemoryBank::<4>::try_from("0 2 7 0 1").unwrap_err();
+        assert_eq!(
+            Error::Parse(ParseError::new(1, 9, "expected 4 memory banks")),
+            err
+        );
+
+        let err = MemoryBank::<4>::try_from("1 4294967295 0 0").unwrap_err();
+        assert_eq!(
+            Error::Parse(ParseError::new(
+                1,
+                3,
+                "more than 4294967295 blocks in total"
+            )),
+            err
+        );
+    }
+
+    #[test]
+    fn redistribute_handles_large_banks() {
+        let input = "255 255 0 0 0 0 0 0 0 0 0 0 0 0 0 0";
+        let memory_bank = MemoryBank::<16>::try_from(input).unwrap();
+
+        // The first bank's 255 blocks go round 15 times, then once more to all but itself
+        let mut expected = [16; 16];
+        expected[0] = 15;
+        expected[1] = 255 + 16;
+        assert_eq!(expected, memory_bank.redistribute().buckets);
+    }
+
+    #[test]
+    fn redistribute_handles_huge_banks() {
+        let memory_bank = MemoryBank::<4>::try_from("1 4000000000 2 3").unwrap();
+
+        let expected = MemoryBank {
+            buckets: [1_000_000_001, 1_000_000_000, 1_000_000_002, 1_000_000_003],
+        };
+        assert_eq!(expected, memory_bank.redistribute());
     }
 }
@@ -1,7 +1,37 @@
+use anyhow::anyhow;
 use chumsky::prelude::*;
+use itertools::Itertools;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use crate::error::{Error, ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Input = ProgramTree;
+    type Part1 = String;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(ProgramTree::build_tree(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.root.clone())
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        match input.find_branch_weight(&input.root)? {
+            Balance::Balanced(_) => Err(anyhow!("The tower is already balanced")),
+            Balance::Corrected(weight) => Ok(weight),
+        }
+    }
+}
+
 pub struct ProgramTree {
     pub root: String,
     tree: HashMap<String, Vec<String>>,
@@ -9,81 +39,163 @@ pub struct ProgramTree {
 }
 
 impl ProgramTree {
-    pub fn build_tree(input: &str) -> Self {
-        let input = Input::from(input);
-        let mut has_parent: HashSet<String> = HashSet::new();
-        let mut tree = HashMap::with_capacity(input.0.len());
-        let mut weights = HashMap::with_capacity(input.0.len());
+    pub fn build_tree(input: &str) -> Result<Self> {
+        let rows = Input::try_from(input)?;
+        let mut declared: HashMap<&str, usize> = HashMap::with_capacity(rows.0.len());
+        let mut tree = HashMap::with_capacity(rows.0.len());
+        let mut weights = HashMap::with_capacity(rows.0.len());
+
+        for (line_no, row) in &rows.0 {
+            if let Some(first) = declared.insert(&row.parent, *line_no) {
+                let message = format!("{:?} is already declared on line {}", row.parent, first);
+                return Err(ParseError::new(*line_no, 1, message).into());
+            }
+            weights.insert(row.parent.clone(), row.weight);
+            tree.insert(row.parent.clone(), row.children.clone().unwrap_or_default());
+        }
 
-        for InputRow {
-            parent,
-            weight,
-            children,
-        } in input.0
-        {
-            weights.insert(parent.clone(), weight);
-            tree.insert(parent.clone(), Vec::new());
-
-            if let Some(children) = children {
-                let c = tree.get_mut(&parent).unwrap();
-                for child in children {
-                    has_parent.insert(child.clone());
-                    c.push(child);
-                }
+        // Every child must have its own line, or its weight and children are unknown, and only
+        // one parent, or the programs don't form a tower
+        let mut parents: HashMap<&str, &str> = HashMap::new();
+        for (line_no, row) in &rows.0 {
+            for child in row.children.iter().flatten() {
+                let message = if !tree.contains_key(child) {
+                    format!("{:?} is never declared", child)
+                } else if let Some(parent) = parents.insert(child, &row.parent) {
+                    format!("{:?} is already held by {:?}", child, parent)
+                } else {
+                    continue;
+                };
+
+                return Err(child_error(input, *line_no, child, message));
             }
         }
 
-        let all_nodes: HashSet<String> = tree.keys().cloned().collect();
-        let root = all_nodes.difference(&has_parent).next().unwrap().clone();
+        let mut roots = rows
+            .0
+            .iter()
+            .filter(|(_, row)| !parents.contains_key(row.parent.as_str()));
+        let root = match (roots.next(), roots.next()) {
+            (Some((_, root)), None) => root.parent.clone(),
+            (None, _) => {
+                let message = "no program is at the bottom of the tower";
+                return Err(ParseError::new(1, 1, message).into());
+            }
+            (Some((_, first)), Some((line_no, second))) => {
+                let message = format!(
+                    "both {:?} and {:?} are at the bottom of the tower",
+                    first.parent, second.parent
+                );
+                return Err(ParseError::new(*line_no, 1, message).into());
+            }
+        };
+
+        // With one parent each, anything the root doesn't hold up is held up by a loop
+        let mut reachable = HashSet::from([root.as_str()]);
+        let mut stack = vec![root.as_str()];
+        while let Some(node) = stack.pop() {
+            for child in &tree[node] {
+                reachable.insert(child.as_str());
+                stack.push(child);
+            }
+        }
+        if let Some((line_no, row)) = rows
+            .0
+            .iter()
+            .find(|(_, row)| !reachable.contains(row.parent.as_str()))
+        {
+            let message = format!("{:?} is part of a loop of programs", row.parent);
+            return Err(ParseError::new(*line_no, 1, message).into());
+        }
 
-        ProgramTree {
+        Ok(ProgramTree {
             root,
             tree,
             weights,
-        }
+        })
     }
 
-    /// Returns Ok(branch_weight) or Err(required_node_weight) if the children are unbalanced.
-    pub fn find_branch_weight(&self, node: &str) -> Result<u32, u32> {
-        let children_weights: Vec<u32> = self.tree[node]
-            .iter()
-            .map(|child| self.find_branch_weight(child))
-            .collect::<Result<_, _>>()?;
-
-        let mut occurences: HashMap<u32, u32> = HashMap::new();
-
-        for weight in children_weights.iter() {
-            let count = occurences.entry(*weight).or_insert(0);
-            *count += 1;
+    /// Weighs the tower held up by `node`, finding the corrected weight of the program that
+    /// unbalances it, if any.
+    pub fn find_branch_weight(&self, node: &str) -> Result<Balance> {
+        let mut children_weights = Vec::with_capacity(self.tree[node].len());
+        for child in &self.tree[node] {
+            match self.find_branch_weight(child)? {
+                Balance::Balanced(weight) => children_weights.push(weight),
+                corrected => return Ok(corrected),
+            }
         }
 
+        let occurences = children_weights.iter().copied().counts();
+
         if occurences.len() > 1 {
-            // There will only be 1 unbalanced weight, as specified by the problem
-            let unbalanced_weight = occurences.into_iter().find(|(_, v)| *v == 1).unwrap().0;
-            let desired_weight = *children_weights
+            // Only one program's weight may be wrong, so every other tower must weigh the same
+            let (unbalanced_weight, desired_weight) = match occurences
                 .iter()
-                .find(|&&w| w != unbalanced_weight)
-                .unwrap();
-
-            let delta = desired_weight as i32 - unbalanced_weight as i32;
+                .sorted_by_key(|&(_, &count)| count)
+                .collect_vec()[..]
+            {
+                [(&unbalanced, 1), (&desired, &count)] if count > 1 => (unbalanced, desired),
+                _ => {
+                    return Err(Error::Unsolvable(format!(
+                        "The towers held by {:?} can't be balanced by changing one weight",
+                        node
+                    )))
+                }
+            };
 
             let index = children_weights
                 .iter()
-                .enumerate()
-                .find(|&(_, &w)| w == unbalanced_weight)
-                .unwrap()
-                .0;
-
+                .position(|&w| w == unbalanced_weight)
+                .expect("the unbalanced weight is one of the children's");
             let child = &self.tree[node][index];
             let child_weight = self.weights[child];
 
-            return Err((child_weight as i32 + delta as i32) as u32);
+            let corrected =
+                i64::from(child_weight) + i64::from(desired_weight) - i64::from(unbalanced_weight);
+
+            return u32::try_from(corrected)
+                .map(Balance::Corrected)
+                .map_err(|_| {
+                    Error::Unsolvable(format!("{:?} would need to weigh {}", child, corrected))
+                });
         }
 
-        Ok(self.weights[node] + children_weights.iter().sum::<u32>())
+        children_weights
+            .iter()
+            .try_fold(self.weights[node], |sum, &weight| sum.checked_add(weight))
+            .map(Balance::Balanced)
+            .ok_or_else(|| {
+                Error::Unsolvable(format!(
+                    "The tower held by {:?} weighs more than {}",
+                    node,
+                    u32::MAX
+                ))
+            })
     }
 }
 
+/// What weighing a tower found.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Balance {
+    /// Every tower in it is balanced, and this is its total weight
+    Balanced(u32),
+    /// The weight that would balance it, for the one program whose weight is wrong
+    Corrected(u32),
+}
+
+/// An error about `child`, one of the programs held by the program on line `line_no`.
+fn child_error(input: &str, line_no: usize, child: &str, message: String) -> Error {
+    let line = input.lines().nth(line_no - 1).unwrap_or_default();
+    let token = line
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .skip_while(|&token| token != "->")
+        .find(|&token| token == child)
+        .unwrap_or(line);
+
+    ParseError::at_token(line_no, line, token, message).into()
+}
+
 #[derive(Debug, PartialEq)]
 struct InputRow {
     parent: String,
@@ -91,26 +203,32 @@ struct InputRow {
     children: Option<Vec<String>>,
 }
 
+/// Each row with the line it was on.
 #[derive(Debug, PartialEq)]
-struct Input(Vec<InputRow>);
-
-impl From<&str> for Input {
-    fn from(input: &str) -> Self {
-        Input(input.lines().map(InputRow::from).collect())
-    }
-}
-
-impl From<&str> for InputRow {
-    fn from(input: &str) -> Self {
-        parse_row(input)
+struct Input(Vec<(usize, InputRow)>);
+
+impl TryFrom<&str> for Input {
+    type Error = Error;
+
+    fn try_from(input: &str) -> Result<Self> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| Ok((i + 1, parse_row(i + 1, line)?)))
+            .collect::<Result<_>>()
+            .map(Input)
     }
 }
 
-fn parse_row(input: &str) -> InputRow {
+fn parse_row(line_no: usize, input: &str) -> Result<InputRow> {
     let name = text::ident::<_, Simple<char>>();
     let weight = text::int(10)
         .delimited_by(just('('), just(')'))
-        .map(|n| n.parse::<u32>().unwrap());
+        .try_map(|n, span| {
+            n.parse::<u32>()
+                .map_err(|e| Simple::custom(span, format!("{}", e)))
+        });
 
     let children = name
         .chain(just(',').padded().ignore_then(name).repeated())
@@ -122,13 +240,15 @@ fn parse_row(input: &str) -> InputRow {
         .padded()
         .then(weight.padded())
         .then(just("->").padded().ignore_then(children).or_not())
+        .then_ignore(end())
         .map(|((parent, weight), children)| InputRow {
             parent,
             weight,
             children,
         });
 
-    row.parse(input).unwrap()
+    row.parse(input)
+        .map_err(|errors| ParseError::from_simple(line_no, input, errors).into())
 }
 
 #[cfg(test)]
@@ -144,53 +264,114 @@ mod tests {
             children: Some(vec!["b".to_owned(), "c".to_owned()]),
         };
 
-        let row = parse_row(input);
+        let row = parse_row(1, input).unwrap();
         assert_eq!(expected, row);
     }
 
     #[test]
-    fn find_root_works() {
+    fn invalid_row_is_reported() {
         let input = "\
             pbga (66)
-            xhth (57)
-            ebii (61)
-            havc (66)
-            ktlj (57)
-            fwft (72) -> ktlj, cntj, xhth
-            qoyq (66)
-            padx (45) -> pbga, havc, qoyq
-            tknk (41) -> ugml, padx, fwft
-            jptl (61)
-            ugml (68) -> gyxo, ebii, jptl
-            gyxo (61)
-            cntj (57)";
-        let expected = "tknk";
-
-        let tree = ProgramTree::build_tree(input);
-
-        assert_eq!(expected, &tree.root);
+            xhth [57]";
+
+        let err = ProgramTree::build_tree(input).err().unwrap();
+
+        assert!(matches!(
+            err,
+            Error::Parse(ParseError {
+                line: 2,
+                column: 18,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn missing_root_is_reported() {
+        let err = ProgramTree::build_tree("").err().unwrap();
+        assert_eq!(
+            Error::Parse(ParseError::new(
+                1,
+                1,
+                "no program is at the bottom of the tower"
+            )),
+            err
+        );
+
+        let err = ProgramTree::build_tree("a (1) -> b\nb (2) -> a")
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Parse(ParseError { line: 1, .. })));
     }
 
     #[test]
-    fn find_unbalanced_node_works() {
+    fn undeclared_child_is_reported() {
         let input = "\
-            pbga (66)
-            xhth (57)
-            ebii (61)
-            havc (66)
-            ktlj (57)
-            fwft (72) -> ktlj, cntj, xhth
-            qoyq (66)
-            padx (45) -> pbga, havc, qoyq
-            tknk (41) -> ugml, padx, fwft
-            jptl (61)
-            ugml (68) -> gyxo, ebii, jptl
-            gyxo (61)
-            cntj (57)";
-
-        let tree = ProgramTree::build_tree(input);
-        let needed_weight = tree.find_branch_weight(&tree.root).unwrap_err();
-
-        assert_eq!(60, needed_weight);
+            a (1) -> b, c
+            b (2)";
+
+        let err = ProgramTree::build_tree(input).err().unwrap();
+        assert_eq!(
+            Error::Parse(ParseError::new(1, 13, "\"c\" is never declared")),
+            err
+        );
+    }
+
+    #[test]
+    fn programs_that_are_not_a_tower_are_reported() {
+        let err = ProgramTree::build_tree("r (1) -> a\na (1) -> b\nb (2) -> a")
+            .err()
+            .unwrap();
+        assert_eq!(
+            Error::Parse(ParseError::new(3, 10, "\"a\" is already held by \"r\"")),
+            err
+        );
+
+        let err = ProgramTree::build_tree("r (1)\na (1) -> b\nb (2) -> a")
+            .err()
+            .unwrap();
+        assert_eq!(
+            Error::Parse(ParseError::new(2, 1, "\"a\" is part of a loop of programs")),
+            err
+        );
+
+        let err = ProgramTree::build_tree("a (1)\nb (2)").err().unwrap();
+        assert_eq!(
+            Error::Parse(ParseError::new(
+                2,
+                1,
+                "both \"a\" and \"b\" are at the bottom of the tower"
+            )),
+            err
+        );
+
+        let err = ProgramTree::build_tree("a (1) -> b\nb (2)\nb (3)")
+            .err()
+            .unwrap();
+        assert_eq!(
+            Error::Parse(ParseError::new(3, 1, "\"b\" is already declared on line 2")),
+            err
+        );
+    }
+
+    #[test]
+    fn ambiguous_imbalance_is_reported() {
+        let tree =
+            ProgramTree::build_tree("r (1) -> a, b, c, d\na (1)\nb (1)\nc (2)\nd (2)").unwrap();
+
+        assert!(matches!(
+            tree.find_branch_weight("r"),
+            Err(Error::Unsolvable(_))
+        ));
+    }
+
+    #[test]
+    fn overweight_tower_is_reported() {
+        let tree = ProgramTree::build_tree("r (4294967295) -> a\na (1)").unwrap();
+
+        assert!(matches!(
+            tree.find_branch_weight("r"),
+            Err(Error::Unsolvable(_))
+        ));
     }
 }
@@ -1,7 +1,40 @@
 use std::collections::HashMap;
 
+use anyhow::anyhow;
 use chumsky::prelude::*;
 
+use crate::error::{Error, ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<Instr>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_program(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        let (registers, _) = run_program(input)?;
+
+        registers
+            .into_values()
+            .max()
+            .ok_or_else(|| anyhow!("No registers were written"))
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let (_, max_held) = run_program(input)?;
+
+        Ok(max_held)
+    }
+}
+
 type Program = Vec<Instr>;
 
 type Registers = HashMap<String, i32>;
@@ -10,33 +43,47 @@ type Registers = HashMap<String, i32>;
 /// program run
 type ProgramOutput = (Registers, i32);
 
-pub fn parse_program(input: &str) -> Program {
-    let parser = instr_parser();
+pub fn parse_program(input: &str) -> Result<Program> {
+    let parser = instr_parser().then_ignore(end());
 
     input
         .lines()
-        .map(|line| parser.parse(line).unwrap())
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parser
+                .parse(line)
+                .map_err(|errors| ParseError::from_simple(i + 1, line, errors).into())
+        })
         .collect()
 }
 
-pub fn run_program(program: &Program) -> ProgramOutput {
+/// Fails if a register overflows.
+pub fn run_program(program: &Program) -> Result<ProgramOutput> {
     let mut registers = Registers::new();
     let mut max = 0;
 
-    for Instr { register, op, cond } in program.iter() {
+    for (i, Instr { register, op, cond }) in program.iter().enumerate() {
         if cond.evaluate_cond(&registers) {
-            let register = registers.entry(register.to_owned()).or_insert(0);
+            let value = registers.entry(register.to_owned()).or_insert(0);
 
-            match op {
-                Op::Inc(v) => *register += v,
-                Op::Dec(v) => *register -= v,
+            *value = match op {
+                Op::Inc(v) => value.checked_add(*v),
+                Op::Dec(v) => value.checked_sub(*v),
             }
-
-            max = std::cmp::max(max, *register);
+            .ok_or_else(|| {
+                Error::Unsolvable(format!(
+                    "Instruction {}: register {} overflows",
+                    i + 1,
+                    register
+                ))
+            })?;
+
+            max = std::cmp::max(max, *value);
         }
     }
 
-    (registers, max)
+    Ok((registers, max))
 }
 
 #[derive(Debug)]
@@ -90,6 +137,11 @@ pub fn instr_parser() -> impl Parser<char, Instr, Error = Simple<char>> {
     let register = text::ident::<_, Simple<char>>().padded();
 
     let op = text::ident()
+        .try_map(|op: String, span| match op.as_str() {
+            "inc" => Ok(Op::Inc as fn(i32) -> Op),
+            "dec" => Ok(Op::Dec as fn(i32) -> Op),
+            _ => Err(Simple::custom(span, "Op must be inc or dec")),
+        })
         .padded()
         .then(
             just('-')
@@ -101,11 +153,7 @@ pub fn instr_parser() -> impl Parser<char, Instr, Error = Simple<char>> {
                         .map_err(|e| Simple::custom(span, format!("{}", e)))
                 }),
         )
-        .try_map(|(op, num), span| match op.as_str() {
-            "inc" => Ok(Op::Inc(num)),
-            "dec" => Ok(Op::Dec(num)),
-            _ => Err(Simple::custom(span, "Op must be inc or dec")),
-        });
+        .map(|(op, num)| op(num));
 
     let cond = just("if")
         .padded()
@@ -154,12 +202,38 @@ mod tests {
             c dec -10 if a >= 1
             c inc -20 if c == 10";
 
-        let program = parse_program(input);
-        let (registers, max_held) = run_program(&program);
+        let program = parse_program(input).unwrap();
+        let (registers, max_held) = run_program(&program).unwrap();
 
-        let max = registers.into_iter().map(|(_, v)| v).max().unwrap();
+        let max = registers.into_values().max().unwrap();
 
         assert_eq!(1, max);
         assert_eq!(10, max_held);
     }
+
+    #[test]
+    fn invalid_instr_is_reported() {
+        let input = "b inc 5 if a > 1\na mul 1 if b < 5";
+        let expected = "\
+Parse error at line 2, column 3: Op must be inc or dec
+  |
+2 | a mul 1 if b < 5
+  |   ^^^";
+
+        let err = parse_program(input).unwrap_err();
+
+        assert_eq!(expected, err.to_string());
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        let program = parse_program("a inc 2147483647 if a == 0\na inc 1 if a > 0").unwrap();
+
+        assert_eq!(
+            Err(Error::Unsolvable(
+                "Instruction 2: register a overflows".to_owned()
+            )),
+            run_program(&program)
+        );
+    }
 }
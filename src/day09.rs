@@ -1,3 +1,28 @@
+use crate::error::{ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+
+    type Input = ProgramOutput;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(process_program(input.trim())?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.0)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(input.1)
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 struct State {
     group_depth: u32,
@@ -28,67 +53,126 @@ impl State {
 }
 
 /// The score of the program and the number of garbage characters
-type ProgramOutput = (u32, u32);
+pub type ProgramOutput = (u32, u32);
 
-pub fn process_program(input: &str) -> ProgramOutput {
+pub fn process_program(input: &str) -> Result<ProgramOutput> {
     use CancelState as C;
     use GarbageState as G;
     let mut state = State::new();
     let mut score = 0;
     let mut garbage_count = 0;
 
+    let (mut line, mut column) = (1, 0);
+    // Where each open group and the open garbage started, to report them if they're never closed
+    let mut open_groups = Vec::new();
+    let mut open_garbage = (0, 0);
+
     for char in input.chars() {
+        if char == '\n' {
+            line += 1;
+            column = 0;
+        } else {
+            column += 1;
+        }
+
         // match order is important for operator precedence
         match (state.group_depth, state.garbage, state.cancel_next, char) {
             (_, _, C::Cancel, _) => state.cancel_next = C::None,
             (_, _, C::None, '!') => state.cancel_next = C::Cancel,
             (_, G::Garbage, _, '>') => state.garbage = G::None,
             (_, G::Garbage, _, _) => garbage_count += 1,
-            (_, G::None, _, '<') => state.garbage = G::Garbage,
-            (_, _, _, '{') => state.group_depth += 1,
+            (_, G::None, _, '<') => {
+                state.garbage = G::Garbage;
+                open_garbage = (line, column);
+            }
+            (_, _, _, '{') => {
+                state.group_depth += 1;
+                open_groups.push((line, column));
+            }
             (_, _, _, ',') => {}
+            (0, _, _, '}') => {
+                return Err(ParseError::new(line, column, "'}' closes no group").into());
+            }
             (depth, _, _, '}') => {
                 score += depth;
-                if depth > 0 {
-                    state.group_depth -= 1;
-                }
+                state.group_depth -= 1;
+                open_groups.pop();
             }
             (_, _, _, c) => {
-                dbg!(state);
-                dbg!(c);
-                panic!("Unhandled state!");
+                let message = format!("unexpected {:?} outside of garbage", c);
+                return Err(ParseError::new(line, column, message).into());
             }
         }
     }
 
-    (score, garbage_count)
+    if let G::Garbage = state.garbage {
+        let (line, column) = open_garbage;
+        return Err(ParseError::new(line, column, "garbage is never closed").into());
+    }
+    if let Some(&(line, column)) = open_groups.last() {
+        return Err(ParseError::new(line, column, "group is never closed").into());
+    }
+
+    Ok((score, garbage_count))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::process_program;
+    use super::*;
+    use crate::error::Error;
 
     #[test]
     fn process_program_score_works() {
         let input = "{{<ab>},{<ab>},{<ab>},{<ab>}}";
-        let score = process_program(input).0;
+        let score = process_program(input).unwrap().0;
         assert_eq!(9, score);
 
         let input = "{{<a!>},{<a!>},{<a!>},{<ab>}}";
-        let score = process_program(input).0;
+        let score = process_program(input).unwrap().0;
         assert_eq!(3, score);
     }
 
     #[test]
     fn process_program_non_garbage_characters_works() {
         let input = "<{o\"i!a,<{i<a>";
-        let garbage_chars = process_program(input).1;
+        let garbage_chars = process_program(input).unwrap().1;
 
         assert_eq!(10, garbage_chars);
 
         let input = "<random characters>";
-        let garbage_chars = process_program(input).1;
+        let garbage_chars = process_program(input).unwrap().1;
 
         assert_eq!(17, garbage_chars);
     }
+
+    #[test]
+    fn unexpected_char_is_reported() {
+        let input = "{{<ab>},x}";
+        let err = process_program(input).unwrap_err();
+
+        assert_eq!(
+            Error::Parse(ParseError::new(1, 9, "unexpected 'x' outside of garbage")),
+            err
+        );
+    }
+
+    #[test]
+    fn unbalanced_groups_and_garbage_are_reported() {
+        assert_eq!(
+            Err(Error::Parse(ParseError::new(1, 3, "'}' closes no group"))),
+            process_program("{}}")
+        );
+        assert_eq!(
+            Err(Error::Parse(ParseError::new(
+                1,
+                2,
+                "garbage is never closed"
+            ))),
+            process_program("{<ab!>")
+        );
+        assert_eq!(
+            Err(Error::Parse(ParseError::new(1, 2, "group is never closed"))),
+            process_program("{{<ab>")
+        );
+    }
 }
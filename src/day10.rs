@@ -1,23 +1,59 @@
 use itertools::Itertools;
 
+use crate::error::{ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = KnotInput;
+    type Part1 = u32;
+    type Part2 = String;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let input = input.trim();
+
+        Ok(KnotInput {
+            lengths: parse_lengths::<256>(input),
+            bytes: input.as_bytes().to_vec(),
+        })
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(part_1(input.lengths.as_ref().map_err(Clone::clone)?))
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(part_2(&input.bytes))
+    }
+}
+
+/// The input read both ways. Part 2 takes any text as bytes, so lengths that don't parse only
+/// fail part 1.
+#[derive(Debug, Clone)]
+pub struct KnotInput {
+    pub lengths: Result<Vec<usize>>,
+    pub bytes: Vec<u8>,
+}
+
 type Skip = usize;
 type TotalRotation = usize;
 
-pub fn part_1(input: &str) -> anyhow::Result<u32> {
-    let lengths: Vec<usize> = input.split(',').map(str::parse).collect::<Result<_, _>>()?;
-
+pub fn part_1(lengths: &[usize]) -> u32 {
     let mut knot = init_knot::<256>();
     let mut skip: Skip = 0;
     let mut total_rotation: TotalRotation = 0;
-    knot_procedure(&lengths, &mut knot, &mut skip, &mut total_rotation);
-    Ok(score(&knot, total_rotation))
+    knot_procedure(lengths, &mut knot, &mut skip, &mut total_rotation);
+    score(&knot, total_rotation)
 }
 
-pub fn part_2(input: &str) -> anyhow::Result<String> {
-    let lengths: Vec<usize> = input
-        .bytes()
-        .map(|b| b as usize)
-        .chain([17, 31, 73, 47, 23].into_iter())
+pub fn part_2(bytes: &[u8]) -> String {
+    let lengths: Vec<usize> = bytes
+        .iter()
+        .map(|&b| b as usize)
+        .chain([17, 31, 73, 47, 23])
         .collect();
 
     let mut knot = init_knot::<256>();
@@ -32,13 +68,37 @@ pub fn part_2(input: &str) -> anyhow::Result<String> {
     let start = (256 - total_rotation) & 255;
     knot.rotate_left(start);
 
-    Ok(knot
-        .into_iter()
+    knot.into_iter()
         .chunks(16)
         .into_iter()
         .map(|chunk| chunk.into_iter().fold(0, |acc, cur| cur ^ acc))
         .map(|byte| format!("{:02x?}", byte))
-        .collect())
+        .collect()
+}
+
+fn parse_lengths<const N: usize>(input: &str) -> Result<Vec<usize>> {
+    input
+        .split(',')
+        .map(|tok| {
+            let length = tok.trim();
+            match length.parse::<usize>() {
+                Ok(length) if length <= N => Ok(length),
+                Ok(_) => Err(ParseError::at_token(
+                    1,
+                    input,
+                    length,
+                    format!("length exceeds {}", N),
+                )),
+                Err(e) => Err(ParseError::at_token(
+                    1,
+                    input,
+                    length,
+                    format!("{:?}: {}", length, e),
+                )),
+            }
+            .map_err(Into::into)
+        })
+        .collect()
 }
 
 fn knot_procedure<const N: usize>(
@@ -75,3 +135,27 @@ fn init_knot<const N: usize>() -> [u8; N] {
     }
     knot
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn parse_lengths_works() {
+        assert_eq!(vec![3, 4, 1, 5], parse_lengths::<5>("3, 4,1,5").unwrap());
+
+        let err = parse_lengths::<5>("3,4,x").unwrap_err();
+        assert_eq!(
+            Error::Parse(ParseError::new(
+                1,
+                5,
+                "\"x\": invalid digit found in string"
+            )),
+            err
+        );
+
+        let err = parse_lengths::<5>("3,6").unwrap_err();
+        assert_eq!(Error::Parse(ParseError::new(1, 3, "length exceeds 5")), err);
+    }
+}
@@ -1,13 +1,41 @@
 use anyhow::anyhow;
 
+use crate::error::{ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Journey;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_journey(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(distance(input).0)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(distance(input).1)
+    }
+}
+
 pub type Journey = Vec<HexVector>;
 
-pub fn parse_journey(input: &str) -> anyhow::Result<Journey> {
+pub fn parse_journey(input: &str) -> Result<Journey> {
     input
         .trim()
         .split(',')
-        .map(HexVector::try_from)
-        .collect::<anyhow::Result<_>>()
+        .map(|step| {
+            HexVector::try_from(step)
+                .map_err(|e| ParseError::at_token(1, input, step, e.to_string()).into())
+        })
+        .collect()
 }
 
 // returns the final distance and the furthest distance
@@ -84,6 +112,7 @@ impl TryFrom<&str> for HexVector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
 
     #[test]
     fn should_find_distance_from_start() {
@@ -108,4 +137,15 @@ mod tests {
         let furthest = distance(&journey).1;
         assert_eq!(expected, furthest);
     }
+
+    #[test]
+    fn unexpected_step_is_reported() {
+        let input = "ne,ne,up,s";
+        let err = parse_journey(input).unwrap_err();
+
+        assert_eq!(
+            Error::Parse(ParseError::new(1, 7, "Unexpected token: up")),
+            err
+        );
+    }
 }
@@ -2,6 +2,35 @@ use chumsky::prelude::*;
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use crate::error::{ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Graph;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(Graph::build_graph(parse_input(input)?))
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        let groups = input.find_groups();
+
+        Ok(groups.values().filter(|&&group| group == 0).count())
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        let groups = input.find_groups();
+
+        Ok(groups.values().collect::<HashSet<_>>().len())
+    }
+}
+
 #[derive(Debug)]
 pub struct Graph {
     inner: HashMap<u32, Vec<u32>>,
@@ -63,14 +92,37 @@ pub struct InputRow {
     adjacent: Vec<u32>,
 }
 
-pub fn parse_input(input: &str) -> Vec<InputRow> {
-    let parser = row_parser();
+pub fn parse_input(input: &str) -> Result<Vec<InputRow>> {
+    let parser = row_parser().then_ignore(end());
 
-    input
-        .trim()
+    let rows = input
         .lines()
-        .filter_map(|line| parser.parse(line).ok())
-        .collect()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parser
+                .parse(line)
+                .map(|row| (i + 1, line, row))
+                .map_err(|errors| ParseError::from_simple(i + 1, line, errors).into())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // Every neighbour needs a line of its own, or nothing is known about who it is connected to
+    let declared: HashSet<u32> = rows.iter().map(|(_, _, row)| row.node).collect();
+    for (line_no, line, row) in &rows {
+        if let Some(&missing) = row.adjacent.iter().find(|node| !declared.contains(node)) {
+            let (_, adjacent) = line.split_once("<->").expect("the row parsed");
+            let token = adjacent
+                .split(',')
+                .map(str::trim)
+                .find(|token| token.parse() == Ok(missing))
+                .expect("the row parsed");
+            let message = format!("program {} has no line of its own", missing);
+            return Err(ParseError::at_token(*line_no, line, token, message).into());
+        }
+    }
+
+    Ok(rows.into_iter().map(|(_, _, row)| row).collect())
 }
 
 // Sample input:
@@ -95,6 +147,7 @@ fn row_parser() -> impl Parser<char, InputRow, Error = Simple<char>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
 
     #[test]
     fn connected_works() {
@@ -107,7 +160,7 @@ mod tests {
             5 <-> 6
             6 <-> 4, 5";
 
-        let input = parse_input(input);
+        let input = parse_input(input).unwrap();
         let graph = Graph::build_graph(input);
 
         let groups = graph.find_groups();
@@ -115,4 +168,32 @@ mod tests {
 
         assert_eq!(6, group_zero_size);
     }
+
+    #[test]
+    fn invalid_row_is_reported() {
+        let input = "\
+            0 <-> 2
+            1 <- 1";
+
+        let err = parse_input(input).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::Parse(ParseError {
+                line: 2,
+                column: 17,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn undeclared_neighbour_is_reported() {
+        let err = parse_input("0 <-> 0, 5\n1 <-> 1").unwrap_err();
+
+        assert_eq!(
+            Error::Parse(ParseError::new(1, 10, "program 5 has no line of its own")),
+            err
+        );
+    }
 }
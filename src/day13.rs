@@ -1,7 +1,31 @@
-use std::collections::HashMap;
-
 use chumsky::prelude::*;
 
+use crate::budget::Budget;
+use crate::error::{Error, ParseError, Result};
+use crate::solution::Solution;
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = Firewall;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(Firewall::parse_firewall(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(input.traverse()?)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(input.traverse_without_detection(&mut Budget::from_defaults())?)
+    }
+}
+
 #[derive(Debug)]
 pub struct Firewall {
     // Pairs of depths and scanner range
@@ -9,36 +33,61 @@ pub struct Firewall {
 }
 
 impl Firewall {
-    pub fn traverse(&self) -> u32 {
+    pub fn traverse(&self) -> Result<u64> {
         self.inner
             .iter()
-            .filter(|&(depth, range)| depth % (2 * (range - 1)) == 0)
-            .map(|(depth, range)| depth * range)
-            .sum()
+            .filter(|&&(depth, range)| u64::from(depth) % period(range) == 0)
+            .try_fold(0u64, |severity, &(depth, range)| {
+                severity.checked_add(u64::from(depth) * u64::from(range))
+            })
+            .ok_or_else(|| Error::Unsolvable(format!("The severity exceeds {}", u64::MAX)))
     }
 
-    pub fn traverse_without_detection(&self) -> Option<u32> {
-        (0..).into_iter().find(|wait| {
-            !self
+    pub fn traverse_without_detection(&self, budget: &mut Budget) -> Result<u64> {
+        if let Some((depth, _)) = self.inner.iter().find(|&&(_, range)| range == 1) {
+            return Err(Error::Unsolvable(format!(
+                "The scanner at depth {} never moves, so it catches every packet",
+                depth
+            )));
+        }
+
+        for wait in 0.. {
+            budget.tick()?;
+
+            if !self
                 .inner
                 .iter()
-                .any(|&(depth, range)| (wait + depth) % (2 * (range - 1)) == 0)
-        })
+                .any(|&(depth, range)| (wait + u64::from(depth)) % period(range) == 0)
+            {
+                return Ok(wait);
+            }
+        }
+
+        unreachable!()
     }
 
-    pub fn parse_firewall(input: &str) -> Firewall {
-        let parser = scanner_parser();
+    pub fn parse_firewall(input: &str) -> Result<Firewall> {
+        let parser = scanner_parser().then_ignore(end());
         let inner = input
-            .trim()
             .lines()
-            .map(|line| parser.parse(line))
-            .collect::<Result<_, _>>()
-            .unwrap();
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parser
+                    .parse(line)
+                    .map_err(|errors| ParseError::from_simple(i + 1, line, errors).into())
+            })
+            .collect::<Result<_>>()?;
 
-        Self { inner }
+        Ok(Self { inner })
     }
 }
 
+/// How many picoseconds a scanner takes to return to the top. One with range 1 is always there.
+fn period(range: u32) -> u64 {
+    (2 * (u64::from(range) - 1)).max(1)
+}
+
 fn scanner_parser() -> impl Parser<char, (u32, u32), Error = Simple<char>> {
     let num = text::int::<_, Simple<char>>(10)
         .padded()
@@ -47,42 +96,99 @@ fn scanner_parser() -> impl Parser<char, (u32, u32), Error = Simple<char>> {
                 .map_err(|e| Simple::custom(span, format!("{}", e)))
         });
 
+    // A scanner with range 0 has nowhere to be
+    let range = num.try_map(|range, span| {
+        if range == 0 {
+            Err(Simple::custom(span, "scanner range must be at least 1"))
+        } else {
+            Ok(range)
+        }
+    });
+
     num.then_ignore(just(':').padded())
-        .then(num)
+        .then(range)
         .map(|(depth, range)| (depth, range))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::budget::Limits;
+    use crate::error::BudgetExhausted;
+
+    #[test]
+    fn unsafe_firewall_exhausts_the_budget() {
+        // The scanners at depths 0 and 1 between them catch every delay
+        let firewall = Firewall::parse_firewall("0: 2\n1: 2").unwrap();
+        let mut budget = Budget::new(Limits {
+            max_iterations: Some(1000),
+            timeout: None,
+        });
+
+        assert!(matches!(
+            firewall.traverse_without_detection(&mut budget),
+            Err(Error::BudgetExhausted(BudgetExhausted {
+                iterations: 1000,
+                ..
+            }))
+        ));
+    }
 
     #[test]
-    fn test_severity() {
+    fn invalid_scanner_is_reported() {
         let input = "\
             0: 3
-            1: 2
-            4: 4
-            6: 4";
-        let expected = 24;
+            1: two";
 
-        let firewall = Firewall::parse_firewall(input);
-        let severity = firewall.traverse();
+        let err = Firewall::parse_firewall(input).unwrap_err();
 
-        assert_eq!(expected, severity);
+        assert!(matches!(
+            err,
+            Error::Parse(ParseError {
+                line: 2,
+                column: 16,
+                ..
+            })
+        ));
     }
 
     #[test]
-    fn test_wait() {
-        let input = "\
-            0: 3
-            1: 2
-            4: 4
-            6: 4";
-        let expected = 10;
+    fn scanner_with_range_0_is_reported() {
+        let err = Firewall::parse_firewall("0: 0").unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::Parse(ParseError {
+                line: 1,
+                column: 4,
+                ref message,
+                ..
+            }) if message == "scanner range must be at least 1"
+        ));
+    }
+
+    #[test]
+    fn scanner_with_range_1_always_catches() {
+        let firewall = Firewall::parse_firewall("0: 3\n3: 1\n4: 1").unwrap();
+
+        assert_eq!(Ok(7), firewall.traverse());
+        assert!(matches!(
+            firewall.traverse_without_detection(&mut Budget::unlimited()),
+            Err(Error::Unsolvable(_))
+        ));
+    }
 
-        let firewall = Firewall::parse_firewall(input);
-        let wait = firewall.traverse_without_detection().unwrap();
+    #[test]
+    fn large_scanners_do_not_overflow() {
+        let firewall = Firewall::parse_firewall("0: 4294967295\n3000000000: 2").unwrap();
+        assert_eq!(Ok(6_000_000_000), firewall.traverse());
+        assert_eq!(
+            Ok(1),
+            firewall.traverse_without_detection(&mut Budget::unlimited())
+        );
 
-        assert_eq!(expected, wait);
+        // Each of these catches the packet with a severity just under 2⁶³
+        let firewall = Firewall::parse_firewall(&"4294967294: 2147483648\n".repeat(3)).unwrap();
+        assert!(matches!(firewall.traverse(), Err(Error::Unsolvable(_))));
     }
 }
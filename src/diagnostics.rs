@@ -0,0 +1,134 @@
+use std::fmt;
+use std::ops::Range;
+
+use chumsky::error::{Simple, SimpleReason};
+
+/// A parse failure rendered against the line of input it occurred in, e.g.
+///
+/// ```text
+/// line 2, column 3: unexpected '-'
+///   |
+/// 2 | 1 -> 1
+///   |   ^
+///   = expected '<'
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub source: String,
+    /// The failing span as char offsets into `source`
+    pub span: Range<usize>,
+    pub message: String,
+    pub expected: Vec<String>,
+}
+
+impl Diagnostic {
+    pub(crate) fn from_simple(line: usize, source: &str, error: &Simple<char>) -> Self {
+        let message = match error.reason() {
+            SimpleReason::Custom(message) => message.clone(),
+            _ => match error.found() {
+                Some(found) => format!("unexpected {:?}", found),
+                None => "unexpected end of line".to_owned(),
+            },
+        };
+
+        let mut expected: Vec<String> = error
+            .expected()
+            .map(|token| match token {
+                Some(c) => format!("{:?}", c),
+                None => "end of line".to_owned(),
+            })
+            .collect();
+        expected.sort();
+
+        Self {
+            line,
+            source: source.to_owned(),
+            span: error.span(),
+            message,
+            expected,
+        }
+    }
+
+    /// 1-based column of the start of the span.
+    pub fn column(&self) -> usize {
+        self.span.start + 1
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        // Keep tabs so that the caret lines up with the source line however it is displayed
+        let padding: String = self
+            .source
+            .chars()
+            .chain(std::iter::repeat(' '))
+            .take(self.span.start)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let carets = "^".repeat(self.span.len().max(1));
+
+        writeln!(
+            f,
+            "line {}, column {}: {}",
+            self.line,
+            self.column(),
+            self.message
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source)?;
+        write!(f, "{} | {}{}", gutter, padding, carets)?;
+
+        match self.expected.as_slice() {
+            [] => Ok(()),
+            [expected] => write!(f, "\n{} = expected {}", gutter, expected),
+            expected => write!(f, "\n{} = expected one of {}", gutter, expected.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_works() {
+        let diagnostic = Diagnostic {
+            line: 12,
+            source: "a mul 1 if b < 5".to_owned(),
+            span: 2..5,
+            message: "Op must be inc or dec".to_owned(),
+            expected: vec![],
+        };
+
+        let expected = "\
+line 12, column 3: Op must be inc or dec
+   |
+12 | a mul 1 if b < 5
+   |   ^^^";
+
+        assert_eq!(expected, diagnostic.to_string());
+    }
+
+    #[test]
+    fn render_lists_expected_tokens() {
+        let diagnostic = Diagnostic {
+            line: 2,
+            source: "\t1 <- 1".to_owned(),
+            span: 5..6,
+            message: "unexpected ' '".to_owned(),
+            expected: vec!["'>'".to_owned()],
+        };
+
+        let expected = "\
+line 2, column 6: unexpected ' '
+  |
+2 | \t1 <- 1
+  | \t    ^
+  = expected '>'";
+
+        assert_eq!(expected, diagnostic.to_string());
+    }
+}
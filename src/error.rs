@@ -0,0 +1,139 @@
+use std::fmt;
+use std::time::Duration;
+
+use chumsky::error::Simple;
+
+use crate::day02::RowPairs;
+use crate::diagnostics::Diagnostic;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Parse(ParseError),
+    BudgetExhausted(BudgetExhausted),
+    /// The input is well-formed but has no answer, e.g. because a value overflows.
+    Unsolvable(String),
+    /// A day 2 row without exactly one evenly divisible pair.
+    DivisiblePairs {
+        line: usize,
+        pairs: RowPairs,
+    },
+}
+
+/// A malformed piece of puzzle input. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// Set for errors from chumsky parsers, which know enough to point at the failing span
+    pub diagnostic: Option<Box<Diagnostic>>,
+}
+
+/// A solver gave up after using up its `Budget`, e.g. because the input never converges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetExhausted {
+    /// Iterations completed before giving up
+    pub iterations: u64,
+    pub limit: Limit,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Limit {
+    Iterations(u64),
+    Timeout(Duration),
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+            diagnostic: None,
+        }
+    }
+
+    /// An error about `token`, which must be a substring of `line`.
+    pub(crate) fn at_token(
+        line_no: usize,
+        line: &str,
+        token: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+        let column = line[..offset].chars().count() + 1;
+
+        Self::new(line_no, column, message)
+    }
+
+    /// Converts the first error a chumsky parser reported for `source`, the text of line `line`.
+    pub(crate) fn from_simple(line: usize, source: &str, errors: Vec<Simple<char>>) -> Self {
+        let error = errors
+            .first()
+            .expect("chumsky reports at least one error on failure");
+        let diagnostic = Diagnostic::from_simple(line, source, error);
+
+        Self {
+            line,
+            column: diagnostic.column(),
+            message: diagnostic.message.clone(),
+            diagnostic: Some(Box::new(diagnostic)),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "{}", e),
+            Error::BudgetExhausted(e) => write!(f, "{}", e),
+            Error::Unsolvable(message) => write!(f, "{}", message),
+            Error::DivisiblePairs { line, pairs } => write!(f, "Line {}: {}", line, pairs),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(diagnostic) = &self.diagnostic {
+            return write!(f, "Parse error at {}", diagnostic);
+        }
+
+        write!(
+            f,
+            "Parse error at line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Budget exhausted after {} iterations: ", self.iterations)?;
+
+        match self.limit {
+            Limit::Iterations(max) => write!(f, "limited to {} iterations", max),
+            Limit::Timeout(timeout) => write!(f, "timed out after {:?}", timeout),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl std::error::Error for ParseError {}
+
+impl std::error::Error for BudgetExhausted {}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<BudgetExhausted> for Error {
+    fn from(e: BudgetExhausted) -> Self {
+        Error::BudgetExhausted(e)
+    }
+}
@@ -0,0 +1,207 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+
+use crate::input::InputSource;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+pub const SESSION_TOKEN_VAR: &str = "SESSION_TOKEN";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already on disk so no request was made
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Finds the session token, preferring `$SESSION_TOKEN` over the contents of `session_file`.
+pub fn session_token(session_file: &Path) -> anyhow::Result<String> {
+    if let Ok(token) = std::env::var(SESSION_TOKEN_VAR) {
+        if !token.trim().is_empty() {
+            return Ok(token.trim().to_owned());
+        }
+    }
+
+    match fs::read_to_string(session_file) {
+        Ok(token) if !token.trim().is_empty() => Ok(token.trim().to_owned()),
+        _ => bail!(
+            "No session token: set ${} or write it to {}",
+            SESSION_TOKEN_VAR,
+            session_file.display()
+        ),
+    }
+}
+
+/// The default session file, `$XDG_CONFIG_HOME/aoc2017/session` or `~/.config/aoc2017/session`.
+/// Each profile has its own, e.g. `session-alice`, as its inputs belong to a different account.
+pub fn default_session_file(profile: Option<&str>) -> PathBuf {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .unwrap_or_default();
+
+    let name = match profile {
+        Some(profile) => format!("session-{}", profile),
+        None => "session".to_owned(),
+    };
+
+    config_dir.join("aoc2017").join(name)
+}
+
+/// The path of the input for `day` if it has already been fetched. Empty files, such as the
+/// placeholders written by `aoc new`, don't count.
+pub fn cached(inputs_dir: &Path, day: u8) -> Option<PathBuf> {
+    let path = input_path(inputs_dir, day);
+
+    fs::metadata(&path)
+        .is_ok_and(|metadata| metadata.len() > 0)
+        .then_some(path)
+}
+
+/// Downloads the input for `day` into `inputs_dir` unless it is already there.
+pub fn fetch(base_url: &str, token: &str, day: u8, inputs_dir: &Path) -> anyhow::Result<Fetched> {
+    if let Some(path) = cached(inputs_dir, day) {
+        return Ok(Fetched::Cached(path));
+    }
+
+    let url = format!("{}/2017/day/{}/input", base_url.trim_end_matches('/'), day);
+    let response = ureq::get(&url)
+        .set("Cookie", &format!("session={}", token))
+        .set("User-Agent", "github.com/ollyswanson/aoc2017")
+        .call();
+
+    let input = match response {
+        Ok(response) => response
+            .into_string()
+            .with_context(|| format!("Failed to read the response from {}", url))?,
+        Err(ureq::Error::Status(404, _)) => bail!("Day {} is not available at {}", day, url),
+        Err(ureq::Error::Status(code, _)) => bail!("{} returned status {}", url, code),
+        Err(e) => return Err(e).with_context(|| format!("Failed to fetch {}", url)),
+    };
+
+    let path = input_path(inputs_dir, day);
+    fs::create_dir_all(inputs_dir)?;
+    fs::write(&path, input).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(Fetched::Downloaded(path))
+}
+
+fn input_path(inputs_dir: &Path, day: u8) -> PathBuf {
+    match InputSource::for_day(inputs_dir, day) {
+        InputSource::File(path) => path,
+        InputSource::Stdin => unreachable!("inputs for a day are always files"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves a single request, answering with the input if the expected session cookie was sent.
+    fn stub_server(body: &'static str) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request: Vec<String> = BufReader::new(stream.try_clone().unwrap())
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|line| !line.is_empty())
+                .collect();
+
+            let response = if request.iter().any(|line| line == "Cookie: session=abc123") {
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            } else {
+                "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n".to_owned()
+            };
+            stream.write_all(response.as_bytes()).unwrap();
+
+            request
+        });
+
+        (base_url, handle)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc2017-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn fetch_downloads_missing_input() {
+        let inputs_dir = temp_dir("fetch-missing");
+        let (base_url, server) = stub_server("0: 3\n1: 2\n");
+
+        let fetched = fetch(&base_url, "abc123", 13, &inputs_dir).unwrap();
+        let request = server.join().unwrap();
+
+        assert_eq!("GET /2017/day/13/input HTTP/1.1", request[0]);
+        assert_eq!(Fetched::Downloaded(inputs_dir.join("day13.txt")), fetched);
+        assert_eq!(
+            "0: 3\n1: 2\n",
+            fs::read_to_string(inputs_dir.join("day13.txt")).unwrap()
+        );
+
+        fs::remove_dir_all(inputs_dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_replaces_empty_placeholder() {
+        let inputs_dir = temp_dir("fetch-placeholder");
+        fs::create_dir_all(&inputs_dir).unwrap();
+        fs::write(inputs_dir.join("day09.txt"), "").unwrap();
+        let (base_url, server) = stub_server("{}");
+
+        let fetched = fetch(&base_url, "abc123", 9, &inputs_dir).unwrap();
+        server.join().unwrap();
+
+        assert_eq!(Fetched::Downloaded(inputs_dir.join("day09.txt")), fetched);
+        assert_eq!(
+            "{}",
+            fs::read_to_string(inputs_dir.join("day09.txt")).unwrap()
+        );
+
+        fs::remove_dir_all(inputs_dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_never_refetches() {
+        let inputs_dir = temp_dir("fetch-cached");
+        fs::create_dir_all(&inputs_dir).unwrap();
+        fs::write(inputs_dir.join("day01.txt"), "1122").unwrap();
+
+        // Nothing is listening here, so any request would fail
+        let fetched = fetch("http://127.0.0.1:9", "abc123", 1, &inputs_dir).unwrap();
+
+        assert_eq!(Fetched::Cached(inputs_dir.join("day01.txt")), fetched);
+        assert_eq!(
+            "1122",
+            fs::read_to_string(inputs_dir.join("day01.txt")).unwrap()
+        );
+
+        fs::remove_dir_all(inputs_dir).unwrap();
+    }
+
+    #[test]
+    fn fetch_reports_bad_status() {
+        let inputs_dir = temp_dir("fetch-status");
+        let (base_url, server) = stub_server("");
+
+        let err = fetch(&base_url, "wrong", 2, &inputs_dir).unwrap_err();
+        server.join().unwrap();
+
+        assert!(err.to_string().ends_with("returned status 400"));
+        assert!(!inputs_dir.join("day02.txt").exists());
+    }
+}
@@ -0,0 +1,80 @@
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+pub const DEFAULT_INPUTS_DIR: &str = "inputs";
+
+/// Where a day's puzzle input is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// `-` selects stdin, anything else is treated as a path.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        }
+    }
+
+    /// The conventional `dayNN.txt` file within `inputs_dir`.
+    pub fn for_day(inputs_dir: &Path, day: u8) -> Self {
+        InputSource::File(inputs_dir.join(format!("day{:02}.txt", day)))
+    }
+
+    pub fn read(&self) -> anyhow::Result<String> {
+        match self {
+            InputSource::File(path) => {
+                if !path.is_file() {
+                    anyhow::bail!("Input file {} does not exist", path.display());
+                }
+
+                std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read input file {}", path.display()))
+            }
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Failed to read input from stdin")?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_arg_works() {
+        assert_eq!(InputSource::Stdin, InputSource::from_arg("-"));
+        assert_eq!(
+            InputSource::File(PathBuf::from("my_input.txt")),
+            InputSource::from_arg("my_input.txt")
+        );
+    }
+
+    #[test]
+    fn for_day_works() {
+        let source = InputSource::for_day(Path::new("inputs"), 7);
+
+        assert_eq!(InputSource::File(PathBuf::from("inputs/day07.txt")), source);
+    }
+
+    #[test]
+    fn missing_file_is_reported() {
+        let source = InputSource::for_day(Path::new("no_such_dir"), 7);
+        let err = source.read().unwrap_err();
+
+        assert_eq!(
+            "Input file no_such_dir/day07.txt does not exist",
+            err.to_string()
+        );
+    }
+}
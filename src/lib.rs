@@ -1,3 +1,6 @@
+pub mod answers;
+pub mod bench;
+pub mod budget;
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -11,3 +14,13 @@ pub mod day10;
 pub mod day11;
 pub mod day12;
 pub mod day13;
+pub mod diagnostics;
+pub mod error;
+pub mod fetch;
+pub mod input;
+pub mod output;
+pub mod profile;
+pub mod registry;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
@@ -0,0 +1,182 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::registry::{Part, Run};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Human,
+    JsonLines,
+    Csv,
+}
+
+/// One answer from the runner, as emitted in every output format.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub parse_ns: u64,
+    pub solve_ns: u64,
+}
+
+impl Record {
+    pub fn from_run(day: u8, run: &Run) -> Vec<Self> {
+        run.parts
+            .iter()
+            .map(|part_run| Record {
+                day,
+                part: match part_run.part {
+                    Part::One => 1,
+                    Part::Two => 2,
+                },
+                answer: part_run.answer.clone(),
+                parse_ns: run.parse_time.as_nanos() as u64,
+                solve_ns: part_run.time.as_nanos() as u64,
+            })
+            .collect()
+    }
+}
+
+/// Writes records in the chosen format, emitting any header before the first record.
+pub struct RecordWriter<W: Write> {
+    format: Format,
+    writer: W,
+    started: bool,
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(format: Format, writer: W) -> Self {
+        Self {
+            format,
+            writer,
+            started: false,
+        }
+    }
+
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            match self.format {
+                Format::Human => writeln!(
+                    self.writer,
+                    "{:>3}  {:>4}  {:<32}  {:>10}  {:>10}",
+                    "Day", "Part", "Answer", "Parse", "Solve"
+                )?,
+                Format::Csv => writeln!(self.writer, "day,part,answer,parse_ns,solve_ns")?,
+                Format::JsonLines => {}
+            }
+        }
+
+        match self.format {
+            Format::Human => writeln!(
+                self.writer,
+                "{:>3}  {:>4}  {:<32}  {:>10.2?}  {:>10.2?}",
+                record.day,
+                record.part,
+                record.answer,
+                Duration::from_nanos(record.parse_ns),
+                Duration::from_nanos(record.solve_ns)
+            ),
+            Format::JsonLines => {
+                serde_json::to_writer(&mut self.writer, record)?;
+                writeln!(self.writer)
+            }
+            Format::Csv => writeln!(
+                self.writer,
+                "{},{},{},{},{}",
+                record.day,
+                record.part,
+                csv_field(&record.answer),
+                record.parse_ns,
+                record.solve_ns
+            ),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "human" => Ok(Format::Human),
+            "json" | "jsonl" => Ok(Format::JsonLines),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("format must be human, json or csv, not {}", format)),
+        }
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 7,
+                part: 1,
+                answer: "azqje".to_owned(),
+                parse_ns: 2000,
+                solve_ns: 500,
+            },
+            Record {
+                day: 7,
+                part: 2,
+                answer: "a,\"b\"".to_owned(),
+                parse_ns: 2000,
+                solve_ns: 1_500_000,
+            },
+        ]
+    }
+
+    fn render(format: Format) -> String {
+        let mut writer = RecordWriter::new(format, Vec::new());
+        for record in records() {
+            writer.write(&record).unwrap();
+        }
+        String::from_utf8(writer.writer).unwrap()
+    }
+
+    #[test]
+    fn json_lines_works() {
+        let expected = r#"{"day":7,"part":1,"answer":"azqje","parse_ns":2000,"solve_ns":500}
+{"day":7,"part":2,"answer":"a,\"b\"","parse_ns":2000,"solve_ns":1500000}
+"#;
+
+        assert_eq!(expected, render(Format::JsonLines));
+    }
+
+    #[test]
+    fn csv_works() {
+        let expected = r#"day,part,answer,parse_ns,solve_ns
+7,1,azqje,2000,500
+7,2,"a,""b""",2000,1500000
+"#;
+
+        assert_eq!(expected, render(Format::Csv));
+    }
+
+    #[test]
+    fn human_works() {
+        let output = render(Format::Human);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(3, lines.len());
+        assert!(lines[0].starts_with("Day  Part  Answer"));
+        assert!(lines[1].ends_with("2.00µs    500.00ns"));
+        assert!(lines[2].ends_with("2.00µs      1.50ms"));
+    }
+}
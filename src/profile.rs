@@ -0,0 +1,78 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{ensure, Context};
+
+/// The directory holding the inputs and `answers.txt` of the named profile, one account's set of
+/// puzzle inputs, which lives in a subdirectory of the inputs directory.
+pub fn profile_dir(inputs_dir: &Path, profile: &str) -> anyhow::Result<PathBuf> {
+    ensure!(
+        is_valid_name(profile),
+        "Profile names may only contain letters, digits, '-' and '_', not {:?}",
+        profile
+    );
+
+    Ok(inputs_dir.join(profile))
+}
+
+/// The names of every profile under `inputs_dir`, in order.
+pub fn list(inputs_dir: &Path) -> anyhow::Result<Vec<String>> {
+    let entries = fs::read_dir(inputs_dir)
+        .with_context(|| format!("Failed to read inputs directory {}", inputs_dir.display()))?;
+
+    let mut profiles = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        if let Some(name) = entry
+            .file_name()
+            .to_str()
+            .filter(|name| is_valid_name(name))
+        {
+            profiles.push(name.to_owned());
+        }
+    }
+
+    profiles.sort();
+    Ok(profiles)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_dir_works() {
+        assert_eq!(
+            PathBuf::from("inputs/alice"),
+            profile_dir(Path::new("inputs"), "alice").unwrap()
+        );
+
+        assert!(profile_dir(Path::new("inputs"), "").is_err());
+        assert!(profile_dir(Path::new("inputs"), "../alice").is_err());
+        assert!(profile_dir(Path::new("inputs"), "alice/day01.txt").is_err());
+    }
+
+    #[test]
+    fn list_works() {
+        let root = std::env::temp_dir().join(format!("aoc2017-profiles-{}", std::process::id()));
+        fs::create_dir_all(root.join("bob")).unwrap();
+        fs::create_dir_all(root.join("alice")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("day01.txt"), "1122").unwrap();
+
+        assert_eq!(vec!["alice", "bob"], list(&root).unwrap());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
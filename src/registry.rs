@@ -0,0 +1,189 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anyhow::bail;
+
+use crate::solution::Solution;
+use crate::*;
+
+type RunFn = fn(&str, &[Part]) -> anyhow::Result<Run>;
+
+pub struct Day {
+    pub number: u8,
+    run: RunFn,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+/// The answers from a single run of a day, along with how long each step took.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub parse_time: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: String,
+    pub time: Duration,
+}
+
+impl Day {
+    pub(crate) const fn new<S: Solution>() -> Self {
+        Self {
+            number: S::DAY,
+            run: run::<S>,
+        }
+    }
+
+    /// Parses the input once and solves each of the requested parts.
+    pub fn run(&self, input: &str, parts: &[Part]) -> anyhow::Result<Run> {
+        (self.run)(input, parts)
+    }
+
+    pub fn solve(&self, input: &str, part: Part) -> anyhow::Result<String> {
+        Ok(self.run(input, &[part])?.parts.remove(0).answer)
+    }
+}
+
+fn run<S: Solution>(input: &str, parts: &[Part]) -> anyhow::Result<Run> {
+    let start = Instant::now();
+    let input = S::parse(input)?;
+    let parse_time = start.elapsed();
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                Part::One => S::part_1(&input).map(|answer| answer.to_string()),
+                Part::Two => S::part_2(&input).map(|answer| answer.to_string()),
+            }?;
+
+            Ok(PartRun {
+                part,
+                answer,
+                time: start.elapsed(),
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(Run { parse_time, parts })
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(part: &str) -> Result<Self, Self::Err> {
+        match part {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("part must be 1 or 2, not {}", part)),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+pub static DAYS: &[Day] = &[
+    Day::new::<day01::Day01>(),
+    Day::new::<day02::Day02>(),
+    Day::new::<day03::Day03>(),
+    Day::new::<day04::Day04>(),
+    Day::new::<day05::Day05>(),
+    Day::new::<day06::Day06>(),
+    Day::new::<day07::Day07>(),
+    Day::new::<day08::Day08>(),
+    Day::new::<day09::Day09>(),
+    Day::new::<day10::Day10>(),
+    Day::new::<day11::Day11>(),
+    Day::new::<day12::Day12>(),
+    Day::new::<day13::Day13>(),
+];
+
+pub fn get(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+/// Resolves a selection such as `7`, `all`, `3..9` or `3..=9` into the registered days it covers.
+pub fn select(selection: &str) -> anyhow::Result<Vec<&'static Day>> {
+    let selection = selection.trim();
+
+    if selection == "all" {
+        return Ok(DAYS.iter().collect());
+    }
+
+    let (start, end) = match selection.split_once("..") {
+        Some((start, end)) => {
+            let start: u8 = start.parse()?;
+            let end: u8 = match end.strip_prefix('=') {
+                Some(end) => end.parse()?,
+                None => end.parse::<u8>()?.saturating_sub(1),
+            };
+            (start, end)
+        }
+        None => {
+            let number = selection.parse()?;
+            if get(number).is_none() {
+                bail!("Day {} has not been solved yet", number);
+            }
+            (number, number)
+        }
+    };
+
+    let days: Vec<_> = DAYS
+        .iter()
+        .filter(|day| (start..=end).contains(&day.number))
+        .collect();
+
+    if days.is_empty() {
+        bail!("No solved days in {}", selection);
+    }
+
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(selection: &str) -> Vec<u8> {
+        select(selection)
+            .unwrap()
+            .into_iter()
+            .map(|day| day.number)
+            .collect()
+    }
+
+    #[test]
+    fn select_works() {
+        assert_eq!(vec![7], numbers("7"));
+        assert_eq!(vec![3, 4, 5, 6, 7, 8], numbers("3..9"));
+        assert_eq!(vec![3, 4, 5, 6, 7, 8, 9], numbers("3..=9"));
+        assert_eq!(DAYS.len(), numbers("all").len());
+    }
+
+    #[test]
+    fn select_rejects_unknown_days() {
+        assert!(select("25").is_err());
+        assert!(select("20..25").is_err());
+        assert!(select("seven").is_err());
+    }
+}
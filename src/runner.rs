@@ -0,0 +1,119 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+use rayon::prelude::*;
+
+use crate::registry::{Day, Part, Run};
+
+/// How running a single day ended. Errors and panics are kept per day so that one bad day doesn't
+/// stop the others from being reported.
+#[derive(Debug)]
+pub enum Outcome {
+    Solved(Run),
+    Failed(anyhow::Error),
+    Panicked(String),
+}
+
+/// Runs `day`, catching any panic from its parser or solvers.
+pub fn run_isolated(day: &Day, input: &str, parts: &[Part]) -> Outcome {
+    match panic::catch_unwind(AssertUnwindSafe(|| day.run(input, parts))) {
+        Ok(Ok(run)) => Outcome::Solved(run),
+        Ok(Err(e)) => Outcome::Failed(e),
+        Err(payload) => Outcome::Panicked(panic_message(payload)),
+    }
+}
+
+/// Runs every day concurrently on a pool of `jobs` threads, or one per core if `None`, returning
+/// the outcomes in the same order as `days`. `read_input` is called on the worker threads too.
+pub fn run_parallel<F>(
+    days: &[&'static Day],
+    parts: &[Part],
+    jobs: Option<usize>,
+    read_input: F,
+) -> anyhow::Result<Vec<Outcome>>
+where
+    F: Fn(&Day) -> anyhow::Result<String> + Sync,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .build()?;
+
+    Ok(pool.install(|| {
+        days.par_iter()
+            .map(|day| match read_input(day) {
+                Ok(input) => run_isolated(day, &input, parts),
+                Err(e) => Outcome::Failed(e),
+            })
+            .collect()
+    }))
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panicked with a non-string payload".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Solution;
+
+    struct Panicking;
+
+    impl Solution for Panicking {
+        const DAY: u8 = 25;
+
+        type Input = String;
+        type Part1 = u32;
+        type Part2 = u32;
+
+        fn parse(input: &str) -> anyhow::Result<Self::Input> {
+            Ok(input.to_owned())
+        }
+
+        fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+            Ok(input.parse().unwrap())
+        }
+
+        fn part_2(_: &Self::Input) -> anyhow::Result<Self::Part2> {
+            anyhow::bail!("No part 2")
+        }
+    }
+
+    static PANICKING: Day = Day::new::<Panicking>();
+
+    #[test]
+    fn run_isolated_catches_panics() {
+        let outcome = run_isolated(&PANICKING, "x", &[Part::One]);
+
+        match outcome {
+            Outcome::Panicked(message) => assert!(message.contains("InvalidDigit")),
+            outcome => panic!("expected a panic, got {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn run_parallel_keeps_going_after_failures() {
+        let days = [
+            &PANICKING,
+            crate::registry::get(1).unwrap(),
+            crate::registry::get(2).unwrap(),
+        ];
+
+        let outcomes = run_parallel(&days, &Part::ALL, Some(2), |day| match day.number {
+            1 => Ok("1122".to_owned()),
+            2 => anyhow::bail!("Input file inputs/day02.txt does not exist"),
+            _ => Ok("x".to_owned()),
+        })
+        .unwrap();
+
+        assert!(matches!(outcomes[0], Outcome::Panicked(_)));
+        assert!(matches!(&outcomes[1], Outcome::Solved(run) if run.parts[0].answer == "3"));
+        assert!(matches!(outcomes[2], Outcome::Failed(_)));
+    }
+}
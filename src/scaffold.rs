@@ -0,0 +1,251 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, ensure, Context};
+
+use crate::answers::ANSWERS_FILE;
+use crate::input::DEFAULT_INPUTS_DIR;
+
+const MODULE_TEMPLATE: &str = r#"use anyhow::anyhow;
+
+use crate::error::Result;
+use crate::solution::Solution;
+
+pub struct Day{NN};
+
+impl Solution for Day{NN} {
+    const DAY: u8 = {N};
+
+    type Input = String;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        part_1(input)
+    }
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        part_2(input)
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<String> {
+    Ok(input.trim().to_owned())
+}
+
+pub fn part_1(_input: &str) -> anyhow::Result<u32> {
+    Err(anyhow!("day {NN} is not solved yet"))
+}
+
+pub fn part_2(_input: &str) -> anyhow::Result<u32> {
+    Err(anyhow!("day {NN} is not solved yet"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore = "add the example from the puzzle description"]
+    fn part_1_works() {
+        let input = "";
+        let expected = 0;
+
+        let input = parse_input(input).unwrap();
+
+        assert_eq!(expected, part_1(&input).unwrap());
+    }
+
+    #[test]
+    #[ignore = "add the example from the puzzle description"]
+    fn part_2_works() {
+        let input = "";
+        let expected = 0;
+
+        let input = parse_input(input).unwrap();
+
+        assert_eq!(expected, part_2(&input).unwrap());
+    }
+}
+"#;
+
+/// Generates the skeleton for `day` under the crate at `root`: the module, its `lib.rs` and
+/// registry entries, placeholder answers and an empty input file unless one was already fetched.
+/// Returns the files that were created or modified.
+pub fn scaffold(root: &Path, day: u8) -> anyhow::Result<Vec<PathBuf>> {
+    ensure!(
+        (1..=25).contains(&day),
+        "Day must be between 1 and 25, not {}",
+        day
+    );
+
+    let module = format!("day{:02}", day);
+    let module_path = root.join("src").join(format!("{}.rs", module));
+    let lib_path = root.join("src/lib.rs");
+    let registry_path = root.join("src/registry.rs");
+    let inputs_dir = root.join(DEFAULT_INPUTS_DIR);
+    let input_path = inputs_dir.join(format!("{}.txt", module));
+    let answers_path = inputs_dir.join(ANSWERS_FILE);
+
+    if module_path.exists() {
+        bail!("{} already exists", module_path.display());
+    }
+
+    let lib = read(&lib_path)?;
+    let registry = read(&registry_path)?;
+    let answers = if answers_path.exists() {
+        read(&answers_path)?
+    } else {
+        String::new()
+    };
+
+    let lib = insert_sorted(&lib, "pub mod day", &format!("pub mod {};", module))
+        .with_context(|| format!("{} already declares {}", lib_path.display(), module))?;
+    let registry = insert_sorted(
+        &registry,
+        "    Day::new::<day",
+        &format!("    Day::new::<{}::Day{:02}>(),", module, day),
+    )
+    .with_context(|| format!("{} already registers {}", registry_path.display(), module))?;
+
+    let module_source = MODULE_TEMPLATE
+        .replace("{NN}", &format!("{:02}", day))
+        .replace("{N}", &day.to_string());
+
+    fs::write(&module_path, module_source)?;
+    fs::write(&lib_path, lib)?;
+    fs::write(&registry_path, registry)?;
+    let mut written = vec![module_path, lib_path, registry_path];
+
+    if answers.lines().all(|line| !is_answer_for(line, day)) {
+        let mut answers = answers;
+        if !answers.is_empty() && !answers.ends_with('\n') {
+            answers.push('\n');
+        }
+        answers.push_str(&format!("{} 1\n{} 2\n", day, day));
+
+        fs::create_dir_all(&inputs_dir)?;
+        fs::write(&answers_path, answers)?;
+        written.push(answers_path);
+    }
+
+    if !input_path.exists() {
+        fs::write(&input_path, "")?;
+        written.push(input_path);
+    }
+
+    Ok(written)
+}
+
+fn read(path: &Path) -> anyhow::Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn is_answer_for(line: &str, day: u8) -> bool {
+    line.split_whitespace().next() == Some(&day.to_string())
+}
+
+/// Inserts `new_line` among the run of lines starting with `prefix`, keeping them in order. Returns
+/// `None` if the line is already present.
+fn insert_sorted(source: &str, prefix: &str, new_line: &str) -> Option<String> {
+    let mut lines: Vec<&str> = source.lines().collect();
+
+    if lines.contains(&new_line) {
+        return None;
+    }
+
+    let matching: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.starts_with(prefix))
+        .map(|(i, _)| i)
+        .collect();
+
+    let index = matching
+        .iter()
+        .copied()
+        .find(|&i| lines[i] > new_line)
+        .or_else(|| matching.last().map(|&i| i + 1))
+        .unwrap_or(lines.len());
+
+    lines.insert(index, new_line);
+
+    let mut source = lines.join("\n");
+    source.push('\n');
+    Some(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_sorted_works() {
+        let source = "pub mod answers;\npub mod day01;\npub mod day03;\npub mod registry;\n";
+
+        let expected =
+            "pub mod answers;\npub mod day01;\npub mod day02;\npub mod day03;\npub mod registry;\n";
+        assert_eq!(
+            Some(expected.to_owned()),
+            insert_sorted(source, "pub mod day", "pub mod day02;")
+        );
+
+        let expected =
+            "pub mod answers;\npub mod day01;\npub mod day03;\npub mod day14;\npub mod registry;\n";
+        assert_eq!(
+            Some(expected.to_owned()),
+            insert_sorted(source, "pub mod day", "pub mod day14;")
+        );
+
+        assert_eq!(None, insert_sorted(source, "pub mod day", "pub mod day03;"));
+    }
+
+    #[test]
+    fn scaffold_works() {
+        let root = std::env::temp_dir().join(format!("aoc2017-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("inputs")).unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "pub mod day01;\npub mod registry;\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/registry.rs"),
+            "pub static DAYS: &[Day] = &[\n    Day::new::<day01::Day01>(),\n];\n",
+        )
+        .unwrap();
+        fs::write(root.join("inputs/answers.txt"), "1 1 1089\n1 2 1156\n").unwrap();
+        fs::write(root.join("inputs/day14.txt"), "flqrgnkx").unwrap();
+
+        let written = scaffold(&root, 14).unwrap();
+
+        assert_eq!(4, written.len());
+        assert!(read(&root.join("src/day14.rs"))
+            .unwrap()
+            .contains("impl Solution for Day14 {\n    const DAY: u8 = 14;"));
+        assert!(!read(&root.join("src/day14.rs")).unwrap().contains("todo!"));
+        assert_eq!(
+            "pub mod day01;\npub mod day14;\npub mod registry;\n",
+            read(&root.join("src/lib.rs")).unwrap()
+        );
+        assert!(read(&root.join("src/registry.rs"))
+            .unwrap()
+            .contains("    Day::new::<day14::Day14>(),\n];"));
+        assert_eq!(
+            "1 1 1089\n1 2 1156\n14 1\n14 2\n",
+            read(&root.join("inputs/answers.txt")).unwrap()
+        );
+        assert_eq!("flqrgnkx", read(&root.join("inputs/day14.txt")).unwrap());
+
+        assert!(scaffold(&root, 14).is_err());
+        assert!(scaffold(&root, 0).is_err());
+        assert!(scaffold(&root, 26).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,17 @@
+use std::fmt::Display;
+
+/// A puzzle split into a parse step and its two parts, so that tooling can drive every day the
+/// same way.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input>;
+
+    fn part_1(input: &Self::Input) -> anyhow::Result<Self::Part1>;
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Self::Part2>;
+}
@@ -0,0 +1,12 @@
+//! Tests generated by `build.rs` from the inputs and expected answers in `examples/`.
+
+use aoc2017::registry::{self, Part};
+
+fn check(day: u8, input: &str, part: Part, expected: &str) {
+    let day = registry::get(day).expect("example for an unregistered day");
+    let answer = day.solve(input, part).unwrap();
+
+    assert_eq!(expected, answer);
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));